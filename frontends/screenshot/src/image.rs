@@ -0,0 +1,140 @@
+//! Encoding of the display as PBM or PNG images
+
+use chip8::frontend::MONO_COLORS;
+use chip8::Chip8;
+
+/// Signature starting every PNG file
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// Largest block of uncompressed data in a zlib stream, in bytes
+const MAX_STORED_BLOCK: usize = 0xFFFF;
+
+/// File formats of the screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Portable bitmap, black and white
+    Pbm,
+
+    /// Portable Network Graphics, in colour
+    Png,
+}
+
+impl Format {
+    /// Format of a file, from the extension of its `path`
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "pbm" => Some(Self::Pbm),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Encode the display of `chip8` in `format`, each pixel becoming a `scale` by `scale` square
+pub fn encode(chip8: &Chip8, format: Format, scale: usize) -> Vec<u8> {
+    let (width, height) = (chip8.screen_width() * scale, chip8.screen_height() * scale);
+    let color = |x: usize, y: usize| {
+        let idx = x / scale + y / scale * chip8.screen_width();
+        match chip8.screen_colors() {
+            // MEGA-CHIP mode, pixels are ARGB
+            Some(colors) => colors[idx] & 0xFF_FFFF,
+            None => MONO_COLORS[chip8.screen()[idx] as usize],
+        }
+    };
+
+    match format {
+        Format::Pbm => {
+            let mut pbm = format!("P4\n{width} {height}\n").into_bytes();
+            for y in 0..height {
+                // Rows are padded to whole bytes, set bits being black
+                for byte_x in (0..width).step_by(8) {
+                    let byte = (byte_x..(byte_x + 8).min(width))
+                        .filter(|&x| color(x, y) == 0)
+                        .fold(0, |byte, x| byte | 0x80 >> (x - byte_x));
+                    pbm.push(byte);
+                }
+            }
+            pbm
+        }
+        Format::Png => {
+            let mut scanlines = Vec::with_capacity((width * 3 + 1) * height);
+            for y in 0..height {
+                // No filter
+                scanlines.push(0);
+                for x in 0..width {
+                    scanlines.extend_from_slice(&color(x, y).to_be_bytes()[1..]);
+                }
+            }
+
+            let mut header = Vec::with_capacity(13);
+            header.extend_from_slice(&(width as u32).to_be_bytes());
+            header.extend_from_slice(&(height as u32).to_be_bytes());
+            // 8 bits per channel, RGB, deflate, no filter, no interlace
+            header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+            let mut png = PNG_SIGNATURE.to_vec();
+            write_chunk(&mut png, b"IHDR", &header);
+            write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+            write_chunk(&mut png, b"IEND", &[]);
+            png
+        }
+    }
+}
+
+/// Append the PNG chunk `kind` holding `data` to `png`
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wrap `data` in a zlib stream made of uncompressed blocks
+pub(crate) fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate, 32 KiB window, no dictionary, fastest compression
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(last as u8);
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+/// CRC-32 of `data`, as used by PNG chunks
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Adler-32 checksum of `data`, ending zlib streams
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,57 @@
+mod image;
+mod options;
+mod script;
+
+#[cfg(test)]
+mod tests;
+
+use chip8::frontend::Driver;
+use chip8::Chip8;
+use options::{usage, Options};
+use script::Scripted;
+use std::{env, fs, process};
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", usage());
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let path = &options.rom;
+    let rom = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Could not read ROM `{path}`: {e}");
+        process::exit(1);
+    });
+
+    let mut chip8 = Chip8::with_platform(options.platform);
+    chip8.set_quirks(options.quirks);
+    chip8.set_clock_hz(options.clock_hz);
+    chip8.set_random_seed(options.seed);
+    if let Err(e) = chip8.load_cartridge(&rom) {
+        eprintln!("Could not load ROM `{path}`: {e}");
+        process::exit(1);
+    }
+
+    // Emulate as fast as possible, frames are only paced for display
+    let mut driver = Driver::new(chip8, Scripted::new(options.presses, options.frames));
+    while driver.frame() {}
+
+    // The display of a faulty program is still worth a screenshot
+    if let Some(message) = driver.frontend().halt_message() {
+        eprintln!("{message}");
+    }
+
+    let image = image::encode(driver.chip8(), options.format, options.scale);
+    let output = &options.output;
+    if let Err(e) = fs::write(output, image) {
+        eprintln!("Could not write `{output}`: {e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,125 @@
+//! Command line options
+
+use crate::image::Format;
+use crate::script::KeyPress;
+use chip8::frontend::parse_option;
+use chip8::{Platform, Quirks, DEFAULT_CLOCK_HZ};
+
+/// Help printed by `--help` and after invalid arguments
+pub fn usage() -> String {
+    let platforms = Platform::ALL.map(Platform::name).join(", ");
+    let quirks = Quirks::PRESETS.map(|(name, _)| name).join(", ");
+    format!(
+        "\
+Usage: chip8-screenshot [OPTIONS] <ROM>
+
+Run a ROM without a window and write its display to an image file.
+
+Options:
+    --frames <N>          Number of frames to emulate, 60 per second (default: 60)
+    --key <PRESS>         Hold hexadecimal key KEY as <KEY>@<START>[-<END>], from frame START until frame
+                          END or during frame START only; may be repeated
+    --output <FILE>       Image to write, as PNG or PBM according to its extension (default: screenshot.png)
+    --scale <N>           Size of the pixels in the image, from 1 to {MAX_SCALE} (default: 1)
+    --speed <HZ>          Instructions executed per second (default: 700)
+    --platform <NAME>     Machine to emulate: {platforms} (default: chip8)
+    --quirks <PRESET>     Interpreter to behave like: {quirks} (default: the platform's)
+    --seed <N>            Seed of the random numbers (default: 0, so screenshots are reproducible)
+    -h, --help            Print this message"
+    )
+}
+
+/// Largest size of the pixels, making MEGA-CHIP images 16384 by 12288 pixels
+const MAX_SCALE: usize = 64;
+
+/// Settings of a screenshot
+pub struct Options {
+    /// Path of the ROM
+    pub rom: String,
+
+    /// Number of frames to emulate
+    pub frames: u32,
+
+    /// Keys to press
+    pub presses: Vec<KeyPress>,
+
+    /// Path of the image to write
+    pub output: String,
+
+    /// Format of the image to write
+    pub format: Format,
+
+    /// Size of the pixels in the image
+    pub scale: usize,
+
+    /// Speed of the CPU, in instructions per second
+    pub clock_hz: u32,
+
+    /// Behaviours that differ between interpreters
+    pub quirks: Quirks,
+
+    /// Machine to emulate
+    pub platform: Platform,
+
+    /// Seed of the random numbers
+    pub seed: u64,
+}
+
+impl Options {
+    /// Parse the command line arguments, without the program name<br>
+    /// `Ok(None)` means that the help was requested
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut rom = None;
+        let mut frames = 60;
+        let mut presses = Vec::new();
+        let mut output = String::from("screenshot.png");
+        let mut scale = 1;
+        let mut clock_hz = DEFAULT_CLOCK_HZ;
+        let mut quirks = None;
+        let mut platform = Platform::default();
+        let mut seed = 0;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for `{name}`"))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--frames" => frames = parse_option("--frames", &value("--frames")?)?,
+                "--key" => presses.push(KeyPress::parse(&value("--key")?)?),
+                "--output" => output = value("--output")?,
+                "--scale" => scale = parse_option("--scale", &value("--scale")?)?,
+                "--speed" => clock_hz = parse_option("--speed", &value("--speed")?)?,
+                "--quirks" => quirks = Some(parse_option("--quirks", &value("--quirks")?)?),
+                "--platform" => platform = parse_option("--platform", &value("--platform")?)?,
+                "--seed" => seed = parse_option("--seed", &value("--seed")?)?,
+                _ if arg.starts_with("--") => return Err(format!("Unknown option `{arg}`")),
+                _ if rom.is_none() => rom = Some(arg),
+                _ => return Err(format!("Unexpected argument `{arg}`")),
+            }
+        }
+
+        let rom = rom.ok_or("Missing ROM")?;
+        let format = Format::from_path(&output)
+            .ok_or_else(|| format!("Unknown image format of `{output}`, expected .png or .pbm"))?;
+        if !(1..=MAX_SCALE).contains(&scale) {
+            return Err(format!("The scale must be between 1 and {MAX_SCALE}"));
+        }
+        let quirks = quirks.unwrap_or(platform.spec().quirks);
+        Ok(Some(Self {
+            rom,
+            frames,
+            presses,
+            output,
+            format,
+            scale,
+            clock_hz,
+            quirks,
+            platform,
+            seed,
+        }))
+    }
+}
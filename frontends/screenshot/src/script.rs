@@ -0,0 +1,106 @@
+//! Frontend replaying scripted key presses for a fixed number of frames
+
+use chip8::frontend::Frontend;
+use chip8::{Chip8, Chip8Error, KeyState, KEYS_COUNT};
+
+/// Key held during a range of frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    /// Key of the keypad
+    key: usize,
+
+    /// Frame during which the key is pressed
+    start: u32,
+
+    /// Frame during which the key is released
+    end: u32,
+}
+
+impl KeyPress {
+    /// Parse `<KEY>@<START>[-<END>]`: hexadecimal key `KEY` held from frame `START` until frame
+    /// `END`, or during frame `START` only
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid key press `{s}`, expected <KEY>@<START>[-<END>]");
+        let (key, frames) = s.split_once('@').ok_or_else(invalid)?;
+        let key = usize::from_str_radix(key, 16)
+            .ok()
+            .filter(|&key| key < KEYS_COUNT)
+            .ok_or_else(invalid)?;
+        let (start, end) = match frames.split_once('-') {
+            Some((start, end)) => (start.parse().ok(), end.parse().ok()),
+            None => (
+                frames.parse().ok(),
+                frames
+                    .parse()
+                    .ok()
+                    .and_then(|start: u32| start.checked_add(1)),
+            ),
+        };
+        match (start, end) {
+            (Some(start), Some(end)) if start < end => Ok(Self { key, start, end }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Frontend pressing keys as scripted, and quitting after a fixed number of frames
+#[derive(Debug)]
+pub struct Scripted {
+    /// Keys to press
+    presses: Vec<KeyPress>,
+
+    /// Number of frames to emulate
+    frames: u32,
+
+    /// Number of frames emulated so far
+    frame: u32,
+
+    /// Why the machine halted, if it did because of an error
+    halt_message: Option<String>,
+}
+
+impl Scripted {
+    /// Frontend emulating `frames` frames while pressing `presses`
+    pub fn new(presses: Vec<KeyPress>, frames: u32) -> Self {
+        Self {
+            presses,
+            frames,
+            frame: 0,
+            halt_message: None,
+        }
+    }
+
+    /// Why the machine halted, if it did because of an error
+    pub fn halt_message(&self) -> Option<&str> {
+        self.halt_message.as_deref()
+    }
+}
+
+impl Frontend for Scripted {
+    fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+        if self.frame == self.frames {
+            return None;
+        }
+
+        let mut frame_keys: [Option<KeyState>; KEYS_COUNT] = Default::default();
+        for press in &self.presses {
+            if press.start == self.frame {
+                frame_keys[press.key] = Some(KeyState::Pressed);
+            } else if press.end == self.frame {
+                frame_keys[press.key] = Some(KeyState::Released);
+            }
+        }
+        self.frame += 1;
+        Some(frame_keys)
+    }
+
+    fn present(&mut self, _chip8: &Chip8) {}
+
+    fn set_beeper(&mut self, _on: bool) {}
+
+    fn halted(&mut self, _chip8: &Chip8, error: Option<&Chip8Error>) {
+        if let Some(e) = error {
+            self.halt_message = Some(format!("Emulation halted at frame {}: {e}", self.frame - 1));
+        }
+    }
+}
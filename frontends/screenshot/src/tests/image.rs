@@ -0,0 +1,131 @@
+//! Encoding of the PBM and PNG images
+
+use crate::image::{adler32, crc32, encode, zlib_stored, Format};
+use chip8::Chip8;
+
+/// Create a machine showing the glyph `0` of the font in the top left corner
+fn zero_machine() -> Chip8 {
+    // V0 = 0, I = font sprite of V0, draw 5 rows at (V0, V0)
+    let program: Vec<u8> = [0x6000u16, 0xF029, 0xD005]
+        .iter()
+        .flat_map(|op| op.to_be_bytes())
+        .collect();
+    let mut chip8 = Chip8::default();
+    chip8.load_cartridge(&program).unwrap();
+    for _ in 0..3 {
+        chip8.step().unwrap();
+    }
+    chip8
+}
+
+#[test]
+fn format_from_extension() {
+    assert_eq!(Format::from_path("shot.png"), Some(Format::Png));
+    assert_eq!(Format::from_path("dir.d/shot.PBM"), Some(Format::Pbm));
+    assert_eq!(Format::from_path("shot.jpg"), None);
+    assert_eq!(Format::from_path("shot"), None);
+}
+
+#[test]
+fn checksums() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    assert_eq!(adler32(b""), 1);
+}
+
+#[test]
+fn zlib_stored_blocks() {
+    assert_eq!(
+        zlib_stored(b"abc"),
+        [0x78, 0x01, 0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c', 0x02, 0x4D, 0x01, 0x27]
+    );
+    assert_eq!(
+        zlib_stored(b""),
+        [0x78, 0x01, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x01]
+    );
+
+    // Blocks hold at most 65535 bytes, only the last one being final
+    let stream = zlib_stored(&[7; 0x10000]);
+    assert_eq!(stream.len(), 2 + 5 + 0xFFFF + 5 + 1 + 4);
+    assert_eq!(stream[2..7], [0x00, 0xFF, 0xFF, 0x00, 0x00]);
+    assert_eq!(
+        stream[7 + 0xFFFF..7 + 0xFFFF + 5],
+        [0x01, 0x01, 0x00, 0xFE, 0xFF]
+    );
+}
+
+#[test]
+fn pbm() {
+    let pbm = encode(&zero_machine(), Format::Pbm, 1);
+
+    let header = b"P4\n64 32\n";
+    assert_eq!(pbm[..header.len()], *header);
+    assert_eq!(pbm.len(), header.len() + 8 * 32);
+    let rows: Vec<&[u8]> = pbm[header.len()..].chunks(8).collect();
+    // Set bits are black, lit pixels white
+    assert_eq!(rows[0], [0x0F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(rows[1], [0x6F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(rows[4], rows[0]);
+    assert!(rows[5..]
+        .iter()
+        .all(|row| row.iter().all(|&byte| byte == 0xFF)));
+}
+
+#[test]
+fn pbm_scaled() {
+    let pbm = encode(&zero_machine(), Format::Pbm, 3);
+
+    let header = b"P4\n192 96\n";
+    assert_eq!(pbm[..header.len()], *header);
+    let rows: Vec<&[u8]> = pbm[header.len()..].chunks(24).collect();
+    assert_eq!(rows.len(), 96);
+    // Each pixel becomes 3 by 3: 0xF0 becomes 12 white pixels
+    assert_eq!(rows[2][..3], [0x00, 0x0F, 0xFF]);
+    assert_eq!(rows[3][..3], [0x1F, 0x8F, 0xFF]);
+}
+
+#[test]
+fn png() {
+    let png = encode(&zero_machine(), Format::Png, 1);
+
+    assert_eq!(
+        png[..8],
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']
+    );
+    #[rustfmt::skip]
+    assert_eq!(
+        png[8..33],
+        [
+            0, 0, 0, 13, b'I', b'H', b'D', b'R',
+            0, 0, 0, 64, 0, 0, 0, 32, 8, 2, 0, 0, 0,
+            0x2D, 0xFF, 0xE9, 0xD3,
+        ]
+    );
+    assert_eq!(
+        png[png.len() - 12..],
+        [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+    );
+
+    // One unfiltered scanline of RGB pixels per row
+    let scanlines: Vec<u8> = (0..32)
+        .flat_map(|y| {
+            let row = [0xF0u8, 0x90, 0x90, 0x90, 0xF0]
+                .get(y)
+                .copied()
+                .unwrap_or(0);
+            let pixels = (0..64).flat_map(move |x| {
+                let lit = x < 8 && row & (0x80 >> x) != 0;
+                [if lit { 0xFF } else { 0 }; 3]
+            });
+            [0].into_iter().chain(pixels)
+        })
+        .collect();
+    let idat = zlib_stored(&scanlines);
+    let end = 41 + idat.len();
+    assert_eq!(png[33..37], (idat.len() as u32).to_be_bytes());
+    assert_eq!(png[37..41], *b"IDAT");
+    assert_eq!(png[41..end], idat[..]);
+    assert_eq!(png[end..end + 4], crc32(&png[37..end]).to_be_bytes());
+    assert_eq!(png.len(), end + 4 + 12);
+}
@@ -0,0 +1,3 @@
+mod image;
+mod options;
+mod script;
@@ -0,0 +1,16 @@
+//! Command line options
+
+use crate::options::Options;
+
+/// Parse `args` followed by a ROM path
+fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+    Options::parse(args.iter().chain(&["rom.ch8"]).map(|&arg| arg.to_owned()))
+}
+
+#[test]
+fn scale_is_bounded() {
+    assert_eq!(parse(&["--scale", "64"]).unwrap().unwrap().scale, 64);
+    for scale in ["0", "65", "18446744073709551615"] {
+        assert!(parse(&["--scale", scale]).is_err(), "{scale}");
+    }
+}
@@ -0,0 +1,38 @@
+//! Parsing of the scripted key presses
+
+use crate::script::KeyPress;
+
+#[test]
+fn key_press_during_one_frame() {
+    assert_eq!(KeyPress::parse("5@30"), KeyPress::parse("5@30-31"));
+    assert_eq!(KeyPress::parse("a@0"), KeyPress::parse("A@0-1"));
+}
+
+#[test]
+fn key_press_during_frame_range() {
+    let press = KeyPress::parse("F@10-40").unwrap();
+    assert_eq!(press, KeyPress::parse("f@10-40").unwrap());
+    assert_ne!(press, KeyPress::parse("F@10-41").unwrap());
+}
+
+#[test]
+fn invalid_key_presses() {
+    for s in [
+        "", "5", "@3", "G@3", "10@3", "5@", "5@x", "5@3-", "5@-3", "5@3-x",
+    ] {
+        assert!(KeyPress::parse(s).is_err(), "{s}");
+    }
+}
+
+#[test]
+fn reversed_or_empty_frame_range() {
+    assert!(KeyPress::parse("5@40-10").is_err());
+    assert!(KeyPress::parse("5@10-10").is_err());
+}
+
+#[test]
+fn last_frame_has_no_end() {
+    assert!(KeyPress::parse("5@4294967295").is_err());
+    assert!(KeyPress::parse("5@4294967294-4294967295").is_ok());
+    assert!(KeyPress::parse("5@4294967296").is_err());
+}
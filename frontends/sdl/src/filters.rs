@@ -0,0 +1,295 @@
+//! Post-processing of the display in software: pixel-art upscalers, then CRT-like effects
+
+/// Factor by which the display is enlarged when only effects are selected, so that they have
+/// room to draw within each pixel
+const EFFECTS_SCALE: usize = 3;
+
+/// Enlargement of the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upscaler {
+    /// Scale2x (AdvMAME2x): smooths diagonals by doubling the pixels
+    Scale2x,
+
+    /// Scale3x (AdvMAME3x): smooths diagonals by tripling the pixels
+    Scale3x,
+
+    /// Eric Johnston's EPX, the ancestor of Scale2x: its rules are written differently but give
+    /// the same pixels
+    Epx,
+}
+
+impl Upscaler {
+    /// Factor by which the display is enlarged
+    fn factor(self) -> usize {
+        match self {
+            Self::Scale2x | Self::Epx => 2,
+            Self::Scale3x => 3,
+        }
+    }
+}
+
+/// Effect drawn over the enlarged display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Dark line below each row of pixels, like the gaps between the scanlines of a CRT
+    Scanlines,
+
+    /// Dark outline around each pixel
+    Grid,
+
+    /// Halo of light around lit pixels
+    Glow,
+}
+
+/// Filters selected for the session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filters {
+    /// Enlargement of the display
+    pub upscaler: Option<Upscaler>,
+
+    /// Effects drawn over the enlarged display, in order
+    pub effects: Vec<Effect>,
+}
+
+/// Opaque ARGB pixels, row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width, in pixels
+    pub width: usize,
+
+    /// Height, in pixels
+    pub height: usize,
+
+    /// Pixels, row by row
+    pub pixels: Vec<u32>,
+}
+
+impl Image {
+    /// Pixel at (`x`, `y`), the nearest edge pixel outside of the image
+    fn at(&self, x: isize, y: isize) -> u32 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.pixels[x + y * self.width]
+    }
+}
+
+impl Filters {
+    /// Parse the name of a filter and add it to the selection
+    pub fn add(&mut self, name: &str) -> Result<(), String> {
+        let upscaler = match name {
+            "scale2x" => Upscaler::Scale2x,
+            "scale3x" => Upscaler::Scale3x,
+            "epx" => Upscaler::Epx,
+            _ => {
+                self.effects.push(match name {
+                    "scanlines" => Effect::Scanlines,
+                    "grid" => Effect::Grid,
+                    "glow" => Effect::Glow,
+                    _ => return Err(format!("Unknown filter `{name}`")),
+                });
+                return Ok(());
+            }
+        };
+        if self.upscaler.is_some() {
+            return Err("Only one of scale2x, scale3x and epx can be selected".into());
+        }
+        self.upscaler = Some(upscaler);
+        Ok(())
+    }
+
+    /// Whether no filter is selected
+    pub fn is_empty(&self) -> bool {
+        self.upscaler.is_none() && self.effects.is_empty()
+    }
+
+    /// Enlarge `image` and draw the effects over it
+    pub fn apply(&self, image: Image) -> Image {
+        if self.is_empty() {
+            return image;
+        }
+
+        let (mut image, factor) = match self.upscaler {
+            Some(upscaler) => (upscale(&image, upscaler), upscaler.factor()),
+            None => (nearest(&image, EFFECTS_SCALE), EFFECTS_SCALE),
+        };
+        for effect in &self.effects {
+            match effect {
+                Effect::Scanlines => darken_cells(&mut image, factor, 50, |_, y| y == factor - 1),
+                Effect::Grid => darken_cells(&mut image, factor, 25, |x, y| {
+                    x == factor - 1 || y == factor - 1
+                }),
+                Effect::Glow => glow(&mut image, factor),
+            }
+        }
+        image
+    }
+}
+
+/// Enlarge `image` `factor` times, each pixel becoming a square
+fn nearest(image: &Image, factor: usize) -> Image {
+    let (width, height) = (image.width * factor, image.height * factor);
+    let pixels = (0..width * height)
+        .map(|i| image.pixels[(i % width) / factor + (i / width) / factor * image.width])
+        .collect();
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Enlarge `image` with `upscaler`
+fn upscale(image: &Image, upscaler: Upscaler) -> Image {
+    let factor = upscaler.factor();
+    let width = image.width * factor;
+    let mut pixels = vec![0; width * image.height * factor];
+    for y in 0..image.height as isize {
+        for x in 0..image.width as isize {
+            let at = |dx, dy| image.at(x + dx, y + dy);
+            let mut place = |block: &[u32]| {
+                for (i, &pixel) in block.iter().enumerate() {
+                    let (bx, by) = (
+                        x as usize * factor + i % factor,
+                        y as usize * factor + i / factor,
+                    );
+                    pixels[bx + by * width] = pixel;
+                }
+            };
+            match upscaler {
+                Upscaler::Scale2x => place(&scale2x(at)),
+                Upscaler::Scale3x => place(&scale3x(at)),
+                Upscaler::Epx => place(&epx(at)),
+            }
+        }
+    }
+    Image {
+        width,
+        height: image.height * factor,
+        pixels,
+    }
+}
+
+/// 2x2 block replacing a pixel with Scale2x, `at` giving its neighbours by offset
+fn scale2x(at: impl Fn(isize, isize) -> u32) -> [u32; 4] {
+    let (p, a, b, c, d) = (at(0, 0), at(0, -1), at(1, 0), at(-1, 0), at(0, 1));
+    if a == d || b == c {
+        return [p; 4];
+    }
+    [
+        if c == a { a } else { p },
+        if a == b { b } else { p },
+        if d == c { c } else { p },
+        if b == d { d } else { p },
+    ]
+}
+
+/// 3x3 block replacing a pixel with Scale3x, `at` giving its neighbours by offset
+fn scale3x(at: impl Fn(isize, isize) -> u32) -> [u32; 9] {
+    let (a, b, c) = (at(-1, -1), at(0, -1), at(1, -1));
+    let (d, e, f) = (at(-1, 0), at(0, 0), at(1, 0));
+    let (g, h, i) = (at(-1, 1), at(0, 1), at(1, 1));
+    if b == h || d == f {
+        return [e; 9];
+    }
+    [
+        if d == b { d } else { e },
+        if (d == b && e != c) || (b == f && e != a) {
+            b
+        } else {
+            e
+        },
+        if b == f { f } else { e },
+        if (d == b && e != g) || (d == h && e != a) {
+            d
+        } else {
+            e
+        },
+        e,
+        if (b == f && e != i) || (h == f && e != c) {
+            f
+        } else {
+            e
+        },
+        if d == h { d } else { e },
+        if (h == f && e != g) || (d == h && e != i) {
+            h
+        } else {
+            e
+        },
+        if h == f { f } else { e },
+    ]
+}
+
+/// 2x2 block replacing a pixel with EPX, `at` giving its neighbours by offset
+fn epx(at: impl Fn(isize, isize) -> u32) -> [u32; 4] {
+    let (p, a, b, c, d) = (at(0, 0), at(0, -1), at(1, 0), at(-1, 0), at(0, 1));
+    let neighbours = [a, b, c, d];
+    let alike = |color| neighbours.iter().filter(|&&n| n == color).count();
+    if neighbours.iter().any(|&n| alike(n) >= 3) {
+        return [p; 4];
+    }
+    [
+        if c == a { a } else { p },
+        if a == b { b } else { p },
+        if d == c { c } else { p },
+        if b == d { d } else { p },
+    ]
+}
+
+/// Darken by `percent` the subpixels of each `factor` by `factor` cell for which `inside` is true,
+/// given their position in the cell
+fn darken_cells(
+    image: &mut Image,
+    factor: usize,
+    percent: u32,
+    inside: impl Fn(usize, usize) -> bool,
+) {
+    for (i, pixel) in image.pixels.iter_mut().enumerate() {
+        let (x, y) = (i % image.width, i / image.width);
+        if inside(x % factor, y % factor) {
+            *pixel = scale_color(*pixel, 100 - percent);
+        }
+    }
+}
+
+/// Add a blurred copy of `image` to itself, the blur spreading over one cell of `factor` pixels
+fn glow(image: &mut Image, factor: usize) {
+    let radius = factor as isize;
+    let blur_x = box_blur(image, radius, 1, 0);
+    let blur = box_blur(&blur_x, radius, 0, 1);
+    for (pixel, halo) in image.pixels.iter_mut().zip(&blur.pixels) {
+        *pixel = add_colors(*pixel, scale_color(*halo, 50));
+    }
+}
+
+/// Average every pixel of `image` with its `radius` neighbours on each side along (`dx`, `dy`)
+fn box_blur(image: &Image, radius: isize, dx: isize, dy: isize) -> Image {
+    let count = (2 * radius + 1) as u32;
+    let mut pixels = Vec::with_capacity(image.pixels.len());
+    for y in 0..image.height as isize {
+        for x in 0..image.width as isize {
+            let mut sums = [0; 3];
+            for step in -radius..=radius {
+                let color = image.at(x + step * dx, y + step * dy);
+                for (channel, sum) in sums.iter_mut().enumerate() {
+                    *sum += (color >> (16 - 8 * channel)) & 0xFF;
+                }
+            }
+            let [r, g, b] = sums.map(|sum| sum / count);
+            pixels.push(0xFF00_0000 | (r << 16) | (g << 8) | b);
+        }
+    }
+    Image { pixels, ..*image }
+}
+
+/// `color` with its channels scaled to `percent`
+fn scale_color(color: u32, percent: u32) -> u32 {
+    let channel = |shift: u32| (((color >> shift) & 0xFF) * percent / 100) << shift;
+    0xFF00_0000 | channel(16) | channel(8) | channel(0)
+}
+
+/// Sum of `a` and `b`, channel by channel, saturating at white
+fn add_colors(a: u32, b: u32) -> u32 {
+    let channel = |shift: u32| (((a >> shift) & 0xFF) + ((b >> shift) & 0xFF)).min(0xFF) << shift;
+    0xFF00_0000 | channel(16) | channel(8) | channel(0)
+}
@@ -0,0 +1,352 @@
+//! SDL window, keyboard and audio device hosting the emulator
+
+use crate::filters::{Filters, Image};
+use crate::palette::Palette;
+use crate::persistence::{Persistence, PersistenceFilter};
+use chip8::frontend::{Frontend, KEYPAD_LAYOUT};
+use chip8::{Chip8, Chip8Error, KeyState, KEYS_COUNT};
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::render::{Texture, WindowCanvas};
+use sdl2::video::FullscreenType;
+use sdl2::{hint, EventPump};
+
+/// Keys of a QWERTY keyboard mapped to the keypad, laid out as in [`KEYPAD_LAYOUT`]
+const KEYS: [[Keycode; 4]; 4] = [
+    [Keycode::Num1, Keycode::Num2, Keycode::Num3, Keycode::Num4],
+    [Keycode::Q, Keycode::W, Keycode::E, Keycode::R],
+    [Keycode::A, Keycode::S, Keycode::D, Keycode::F],
+    [Keycode::Z, Keycode::X, Keycode::C, Keycode::V],
+];
+
+/// Key switching between windowed and fullscreen
+const FULLSCREEN_KEY: Keycode = Keycode::F11;
+
+/// Key switching to the next palette
+const PALETTE_KEY: Keycode = Keycode::P;
+
+/// Title of the window, followed by the name of the palette
+const TITLE: &str = "CHIP-8 Emulator";
+
+/// Frequency of the beeper, in Hz
+const BEEPER_HZ: f32 = 440.0;
+
+/// Volume of the beeper, from 0 to 1
+const BEEPER_VOLUME: f32 = 0.25;
+
+/// Square wave played by the beeper
+struct SquareWave {
+    /// Fraction of a period elapsed per sample
+    phase_inc: f32,
+
+    /// Fraction of the current period elapsed
+    phase: f32,
+}
+
+impl AudioCallback for SquareWave {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for sample in out {
+            *sample = if self.phase < 0.5 {
+                BEEPER_VOLUME
+            } else {
+                -BEEPER_VOLUME
+            };
+            self.phase = (self.phase + self.phase_inc) % 1.0;
+        }
+    }
+}
+
+/// Window showing the display, keyboard mapped to the keypad and audio device playing the beeper
+pub struct SdlFrontend {
+    /// Canvas of the window
+    canvas: WindowCanvas,
+
+    /// Texture holding the display, created at its first frame and at each change of resolution<br>
+    /// It belongs to the canvas, which frees it when the window closes
+    texture: Option<Texture>,
+
+    /// ARGB colours of the pixels last uploaded to the texture, before post-processing
+    uploaded: Vec<u32>,
+
+    /// Palettes the hotkey cycles through
+    palettes: Vec<Palette>,
+
+    /// Index of the palette in use
+    palette: usize,
+
+    /// Flicker reduction
+    persistence: PersistenceFilter,
+
+    /// Post-processing, enlarging the display before it is scaled to the window
+    filters: Filters,
+
+    /// Source of the window and keyboard events
+    event_pump: EventPump,
+
+    /// Beeper, if an audio device could be opened
+    beeper: Option<AudioDevice<SquareWave>>,
+
+    /// Last error met while drawing, reported once until drawing succeeds again
+    draw_error: Option<String>,
+}
+
+impl SdlFrontend {
+    /// Open a resizable window of `width` by `height` points, fullscreen if `fullscreen`, and the
+    /// audio device<br>
+    /// The display is scaled to fit the window, keeping its aspect ratio with black bars, and drawn
+    /// with `palette` until the hotkey switches to the presets, previous frames persisting according
+    /// to `persistence`, then post-processed with `filters`
+    pub fn new(
+        width: u32,
+        height: u32,
+        fullscreen: bool,
+        palette: Palette,
+        persistence: Persistence,
+        filters: Filters,
+    ) -> Result<Self, String> {
+        let sdl_context = sdl2::init()?;
+        let video_subsystem = sdl_context.video()?;
+
+        // Scale with nearest-neighbour filtering, to keep the pixels sharp
+        hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
+
+        let title = format!("{TITLE} ({})", palette.name);
+        let mut window = video_subsystem.window(&title, width, height);
+        // On HiDPI screens, render at the resolution of the screen rather than in points
+        window.position_centered().resizable().allow_highdpi();
+        if fullscreen {
+            window.fullscreen_desktop();
+        }
+        let window = window.build().map_err(|e| e.to_string())?;
+        let canvas = window.into_canvas().build().map_err(|e| e.to_string())?;
+
+        // The emulator is still usable without sound
+        let beeper = sdl_context
+            .audio()
+            .and_then(|audio| {
+                let spec = AudioSpecDesired {
+                    freq: Some(44_100),
+                    channels: Some(1),
+                    samples: None,
+                };
+                audio.open_playback(None, &spec, |spec| SquareWave {
+                    phase_inc: BEEPER_HZ / spec.freq as f32,
+                    phase: 0.0,
+                })
+            })
+            .map_err(|e| eprintln!("Could not open audio device: {e}"))
+            .ok();
+
+        // A custom palette comes before the presets
+        let mut palettes = Palette::PRESETS.to_vec();
+        let palette = palettes
+            .iter()
+            .position(|&preset| preset == palette)
+            .unwrap_or_else(|| {
+                palettes.insert(0, palette);
+                0
+            });
+
+        Ok(Self {
+            canvas,
+            texture: None,
+            uploaded: Vec::new(),
+            palettes,
+            palette,
+            persistence: PersistenceFilter::new(persistence),
+            filters,
+            event_pump: sdl_context.event_pump()?,
+            beeper,
+            draw_error: None,
+        })
+    }
+}
+
+impl SdlFrontend {
+    /// Upload `image` to the texture, created again if its resolution changed
+    fn upload(&mut self, image: &Image) -> Result<(), String> {
+        // The resolution may change between frames, and the renderer letterboxes the logical size in
+        // the window
+        let (width, height) = (image.width as u32, image.height as u32);
+        let resized = self.texture.as_ref().is_none_or(|texture| {
+            let query = texture.query();
+            (query.width, query.height) != (width, height)
+        });
+        if resized {
+            let texture = self
+                .canvas
+                .create_texture_streaming(PixelFormatEnum::ARGB8888, width, height)
+                .map_err(|e| e.to_string())?;
+            if let Some(previous) = self.texture.replace(texture) {
+                // SAFETY: the canvas that created the texture is still alive
+                unsafe { previous.destroy() };
+            }
+            self.canvas
+                .set_logical_size(width, height)
+                .map_err(|e| e.to_string())?;
+        }
+
+        let bytes: Vec<u8> = image
+            .pixels
+            .iter()
+            .flat_map(|argb| argb.to_ne_bytes())
+            .collect();
+        let texture = self.texture.as_mut().ok_or("No texture")?;
+        texture
+            .update(None, &bytes, image.width * 4)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Draw the texture in the window, letterboxed
+    fn redraw(&mut self) -> Result<(), String> {
+        self.canvas.set_draw_color(Color::BLACK);
+        self.canvas.clear();
+        if let Some(texture) = &self.texture {
+            self.canvas.copy(texture, None, None)?;
+        }
+        self.canvas.present();
+        Ok(())
+    }
+
+    /// Switch to the next palette, showing its name in the title of the window
+    fn next_palette(&mut self) {
+        self.palette = (self.palette + 1) % self.palettes.len();
+        let title = format!("{TITLE} ({})", self.palettes[self.palette].name);
+        // The title is only informative
+        let _ = self.canvas.window_mut().set_title(&title);
+    }
+}
+
+impl Frontend for SdlFrontend {
+    fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+        let mut frame_keys: [Option<KeyState>; KEYS_COUNT] = Default::default();
+        // The event pump is borrowed while polling
+        let mut next_palette = false;
+        for event in self.event_pump.poll_iter() {
+            let (key, state) = match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => return None,
+                Event::KeyDown {
+                    keycode: Some(FULLSCREEN_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    toggle_fullscreen(&mut self.canvas);
+                    continue;
+                }
+                Event::KeyDown {
+                    keycode: Some(PALETTE_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    next_palette = true;
+                    continue;
+                }
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } => (key, KeyState::Pressed),
+                Event::KeyUp {
+                    keycode: Some(key), ..
+                } => (key, KeyState::Released),
+                _ => continue,
+            };
+            if let Some(i) = keypad_key(key) {
+                frame_keys[i] = Some(state);
+            }
+        }
+        if next_palette {
+            self.next_palette();
+        }
+        Some(frame_keys)
+    }
+
+    fn present(&mut self, chip8: &Chip8) {
+        let (width, height) = (chip8.screen_width(), chip8.screen_height());
+        let (mut pixels, background): (Vec<u32>, u32) = match chip8.screen_colors() {
+            // MEGA-CHIP mode, pixels are ARGB
+            Some(colors) => (
+                colors.iter().map(|argb| argb | 0xFF00_0000).collect(),
+                0xFF00_0000,
+            ),
+            None => {
+                let palette = &self.palettes[self.palette];
+                let pixels = chip8.screen().iter().map(|&px| palette.argb(px)).collect();
+                (pixels, palette.argb(0))
+            }
+        };
+        self.persistence.apply(&mut pixels, background);
+
+        // Most frames leave the display unchanged, sparing the post-processing and the upload
+        let mut result = Ok(());
+        if self.texture.is_none() || pixels != self.uploaded {
+            let image = self.filters.apply(Image {
+                width,
+                height,
+                pixels: pixels.clone(),
+            });
+            result = self.upload(&image);
+            // Upload again at the next frame if this one failed
+            self.uploaded = if result.is_ok() { pixels } else { Vec::new() };
+        }
+        result = result.and_then(|()| self.redraw());
+
+        // The frame is skipped, and the error reported once rather than at every frame
+        match result {
+            Ok(()) => self.draw_error = None,
+            Err(e) if self.draw_error.as_ref() != Some(&e) => {
+                eprintln!("Could not draw the display: {e}");
+                self.draw_error = Some(e);
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn set_beeper(&mut self, on: bool) {
+        if let Some(beeper) = &self.beeper {
+            if on {
+                beeper.resume();
+            } else {
+                beeper.pause();
+            }
+        }
+    }
+
+    fn halted(&mut self, chip8: &Chip8, error: Option<&Chip8Error>) {
+        // Keep showing the last frame until the window is closed
+        if let Some(e) = error {
+            eprintln!("Emulation halted: {e}");
+            if let Some(seed) = chip8.random_seed() {
+                eprintln!("Random seed: {seed}");
+            }
+        }
+    }
+}
+
+/// Switch the window of `canvas` between windowed and fullscreen, covering the desktop without
+/// changing its resolution
+fn toggle_fullscreen(canvas: &mut WindowCanvas) {
+    let window = canvas.window_mut();
+    let fullscreen = match window.fullscreen_state() {
+        FullscreenType::Off => FullscreenType::Desktop,
+        _ => FullscreenType::Off,
+    };
+    if let Err(e) = window.set_fullscreen(fullscreen) {
+        eprintln!("Could not switch to fullscreen: {e}");
+    }
+}
+
+/// Key of the keypad mapped to `keycode`
+fn keypad_key(keycode: Keycode) -> Option<usize> {
+    KEYS.iter()
+        .flatten()
+        .zip(KEYPAD_LAYOUT.iter().flatten())
+        .find(|&(&k, _)| k == keycode)
+        .map(|(_, &i)| i)
+}
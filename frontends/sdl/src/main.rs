@@ -0,0 +1,84 @@
+mod filters;
+mod frontend;
+mod options;
+mod palette;
+mod persistence;
+
+#[cfg(test)]
+mod tests;
+
+use chip8::frontend::Driver;
+use chip8::Chip8;
+use frontend::SdlFrontend;
+use options::{usage, Options};
+use std::io::{self, Read};
+use std::{env, fs, process};
+
+/// Read the ROM designated by `path`, `-` meaning standard input
+fn read_rom(path: &str) -> io::Result<Vec<u8>> {
+    if path == "-" {
+        let mut rom = Vec::new();
+        io::stdin().read_to_end(&mut rom)?;
+        Ok(rom)
+    } else {
+        fs::read(path)
+    }
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", usage());
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let path = &options.rom;
+    let rom = read_rom(path).unwrap_or_else(|e| {
+        eprintln!("Could not read ROM `{path}`: {e}");
+        process::exit(1);
+    });
+
+    let mut chip8 = Chip8::with_platform(options.platform);
+    chip8.set_quirks(options.quirks);
+    chip8.set_clock_hz(options.clock_hz);
+    chip8.set_stack_size(options.stack_size);
+    chip8.set_stack_in_ram(options.stack_in_ram);
+    if let Some(random) = options.vip_random {
+        chip8.set_random_source(random);
+    } else if let Some(seed) = options.seed {
+        chip8.set_random_seed(seed);
+    }
+    if let Err(e) = chip8.set_font(options.font, options.font_addr) {
+        eprintln!("Could not install font: {e}");
+        process::exit(1);
+    }
+    if let Err(e) = chip8.load_cartridge(&rom) {
+        eprintln!("Could not load ROM `{path}`: {e}");
+        process::exit(1);
+    }
+
+    let spec = options.platform.spec();
+    let (width, height) = (
+        spec.screen_width as u32 * options.scale,
+        spec.screen_height as u32 * options.scale,
+    );
+    let frontend = SdlFrontend::new(
+        width,
+        height,
+        options.fullscreen,
+        options.palette,
+        options.persistence,
+        options.filters,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Could not open window: {e}");
+        process::exit(1);
+    });
+    Driver::new(chip8, frontend).run();
+}
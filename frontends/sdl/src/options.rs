@@ -0,0 +1,337 @@
+//! Command line options
+
+use crate::filters::Filters;
+use crate::palette::{parse_color, Palette};
+use crate::persistence::Persistence;
+use chip8::frontend::parse_option;
+use chip8::{FontSet, Platform, Quirks, VipRandom, DEFAULT_CLOCK_HZ, FONT_SPRITES_ADDR};
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::{env, fs};
+
+/// Help printed by `--help` and after invalid arguments
+pub fn usage() -> String {
+    let platforms = Platform::ALL.map(Platform::name).join(", ");
+    let quirks = Quirks::PRESETS.map(|(name, _)| name).join(", ");
+    format!(
+        "\
+Usage: chip8-sdl [OPTIONS] <ROM>
+
+Use `-` as ROM to read it from standard input.
+
+Options are also read from `$XDG_CONFIG_HOME/chip8-sdl.conf` (`~/.config/chip8-sdl.conf` by default)
+and from the file given to `--config`: one `option = value` per line, or just `option` for flags,
+without the leading dashes, `#` starting comments and values being optionally quoted. Command line
+options take precedence.
+
+Options:
+    --config <FILE>       Read options from FILE
+    --font <FONT>         Font set: vip, eti660, dream6800, octo (default) or a path to an 80-byte font file
+    --font-addr <ADDR>    Address of the font in RAM (default: 0x050)
+    --speed <HZ>          Instructions executed per second (default: 700)
+    --scale <N>           Initial size of the pixels in the window, from 1 to {MAX_SCALE} (default: 10)
+    --fullscreen          Start fullscreen; F11 switches between windowed and fullscreen
+    --palette <NAME>      Colours: mono (default), octo, amber, green, lcd or high-contrast; P cycles them
+    --fg <RRGGBB>         Colour of lit pixels, overriding the palette's
+    --bg <RRGGBB>         Colour of unlit pixels, overriding the palette's
+    --colors <C0,C1,C2,C3>
+                          The four XO-CHIP colours: background, first plane, second plane, both planes
+    --phosphor <FRAMES>   Reduce flicker by fading pixels out over FRAMES frames once they turn off
+    --blend <FRAMES>      Reduce flicker by showing the average of the last FRAMES frames
+    --filter <NAMES>      Post-processing, comma separated and repeatable: one upscaler of scale2x,
+                          scale3x or epx, then the effects scanlines, grid and glow, in order
+    --platform <NAME>     Machine to emulate: {platforms} (default: chip8)
+    --quirks <PRESET>     Interpreter to behave like: {quirks} (default: the platform's)
+    --stack-size <N>      Maximum number of nested subroutine calls, or unlimited (default: the platform's)
+    --vip-stack           Keep the stack in RAM at 0xEA0, like the COSMAC VIP
+    --seed <N>            Seed of the random numbers, to reproduce a session (default: random)
+    --vip-random <FILE>   Generate random numbers like the COSMAC VIP, from a dump of its RAM holding
+                          the interpreter, starting at 0x000
+    -h, --help            Print this message"
+    )
+}
+
+/// Default size of the pixels in the window
+const DEFAULT_SCALE: u32 = 10;
+
+/// Largest size of the pixels in the window, making a MEGA-CHIP window 16384 by 12288 points
+const MAX_SCALE: u32 = 64;
+
+/// Settings of an emulation session
+pub struct Options {
+    /// Path of the ROM, `-` meaning standard input
+    pub rom: String,
+
+    /// Font installed in RAM
+    pub font: FontSet,
+
+    /// Address of the font in RAM
+    pub font_addr: u16,
+
+    /// Speed of the CPU, in instructions per second
+    pub clock_hz: u32,
+
+    /// Initial size of the pixels in the window
+    pub scale: u32,
+
+    /// Start fullscreen
+    pub fullscreen: bool,
+
+    /// Colours of the display
+    pub palette: Palette,
+
+    /// Flicker reduction
+    pub persistence: Persistence,
+
+    /// Post-processing of the display
+    pub filters: Filters,
+
+    /// Behaviours that differ between interpreters
+    pub quirks: Quirks,
+
+    /// Machine to emulate
+    pub platform: Platform,
+
+    /// Maximum number of nested subroutine calls, `None` meaning unlimited
+    pub stack_size: Option<usize>,
+
+    /// Keep the stack in RAM, like the COSMAC VIP
+    pub stack_in_ram: bool,
+
+    /// Seed of the random numbers, random if `None`
+    pub seed: Option<u64>,
+
+    /// Generate the random numbers like the COSMAC VIP instead
+    pub vip_random: Option<VipRandom>,
+}
+
+impl Options {
+    /// Parse the command line arguments, without the program name<br>
+    /// `Ok(None)` means that the help was requested
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut rom = None;
+        let mut font = FontSet::default();
+        let mut font_addr = FONT_SPRITES_ADDR;
+        let mut clock_hz = DEFAULT_CLOCK_HZ;
+        let mut scale = DEFAULT_SCALE;
+        let mut fullscreen = false;
+        let mut palette = Palette::default();
+        let mut fg = None;
+        let mut bg = None;
+        let mut colors = None;
+        let mut persistence = Persistence::default();
+        let mut filters = Filters::default();
+        let mut quirks = None;
+        let mut platform = Platform::default();
+        let mut stack_size = None;
+        let mut stack_in_ram = false;
+        let mut seed = None;
+        let mut vip_random = None;
+
+        // Options of the configuration file come first, so that the command line overrides them
+        let mut configs = HashSet::new();
+        let mut pending: VecDeque<String> = match default_config_path() {
+            Some(path) if path.exists() => read_config(&path.to_string_lossy(), &mut configs)?,
+            _ => VecDeque::new(),
+        };
+        pending.extend(args);
+        while let Some(arg) = pending.pop_front() {
+            let mut value = |name: &str| {
+                pending
+                    .pop_front()
+                    .ok_or_else(|| format!("Missing value for `{name}`"))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--config" => {
+                    let config = read_config(&value("--config")?, &mut configs)?;
+                    for arg in config.into_iter().rev() {
+                        pending.push_front(arg);
+                    }
+                }
+                "--font" => font = parse_font(&value("--font")?)?,
+                "--font-addr" => font_addr = parse_u16(&value("--font-addr")?)?,
+                "--speed" => clock_hz = parse_option("--speed", &value("--speed")?)?,
+                "--scale" => scale = parse_scale(&value("--scale")?)?,
+                "--fullscreen" => fullscreen = true,
+                "--palette" => palette = parse_palette(&value("--palette")?)?,
+                "--fg" => fg = Some(parse_color(&value("--fg")?)?),
+                "--bg" => bg = Some(parse_color(&value("--bg")?)?),
+                "--colors" => colors = Some(parse_colors(&value("--colors")?)?),
+                "--phosphor" => {
+                    persistence =
+                        Persistence::Phosphor(parse_option("--phosphor", &value("--phosphor")?)?)
+                }
+                "--blend" => {
+                    persistence = Persistence::Blend(parse_option("--blend", &value("--blend")?)?)
+                }
+                "--filter" => {
+                    for name in value("--filter")?.split(',') {
+                        filters.add(name.trim())?;
+                    }
+                }
+                "--quirks" => quirks = Some(parse_option("--quirks", &value("--quirks")?)?),
+                "--platform" => platform = parse_option("--platform", &value("--platform")?)?,
+                "--stack-size" => stack_size = Some(parse_stack_size(&value("--stack-size")?)?),
+                "--vip-stack" => stack_in_ram = true,
+                "--seed" => seed = Some(parse_option("--seed", &value("--seed")?)?),
+                "--vip-random" => vip_random = Some(value("--vip-random")?),
+                _ if arg.starts_with("--") => return Err(format!("Unknown option `{arg}`")),
+                _ if rom.is_none() => rom = Some(arg),
+                _ => return Err(format!("Unexpected argument `{arg}`")),
+            }
+        }
+
+        let rom = rom.ok_or("Missing ROM")?;
+        if fg.is_some() || bg.is_some() || colors.is_some() {
+            palette.name = "custom";
+            palette.colors = colors.unwrap_or(palette.colors);
+            palette.colors[0] = bg.unwrap_or(palette.colors[0]);
+            palette.colors[1] = fg.unwrap_or(palette.colors[1]);
+        }
+        let quirks = quirks.unwrap_or(platform.spec().quirks);
+        let stack_size = stack_size.unwrap_or(Some(platform.spec().stack_size));
+        let vip_random = match vip_random {
+            Some(path) => Some(load_vip_random(&path, seed.unwrap_or(0))?),
+            None => None,
+        };
+        Ok(Some(Self {
+            rom,
+            font,
+            font_addr,
+            clock_hz,
+            scale,
+            fullscreen,
+            palette,
+            persistence,
+            filters,
+            quirks,
+            platform,
+            stack_size,
+            stack_in_ram,
+            seed,
+            vip_random,
+        }))
+    }
+}
+
+/// Parse a font set name, or load a font file
+fn parse_font(s: &str) -> Result<FontSet, String> {
+    match s {
+        "vip" => Ok(FontSet::CosmacVip),
+        "eti660" => Ok(FontSet::Eti660),
+        "dream6800" => Ok(FontSet::Dream6800),
+        "octo" => Ok(FontSet::Octo),
+        path => {
+            let bytes = fs::read(path).map_err(|e| format!("Could not read font `{path}`: {e}"))?;
+            FontSet::from_bytes(&bytes).map_err(|e| format!("Invalid font `{path}`: {e}"))
+        }
+    }
+}
+
+/// Parse the name of a palette preset
+fn parse_palette(s: &str) -> Result<Palette, String> {
+    Palette::preset(s).ok_or_else(|| format!("Unknown palette `{s}`"))
+}
+
+/// Parse four comma-separated RGB colours
+pub(crate) fn parse_colors(s: &str) -> Result<[u32; 4], String> {
+    let colors = s
+        .split(',')
+        .map(|color| parse_color(color.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    colors
+        .try_into()
+        .map_err(|_| format!("Expected four colours, got `{s}`"))
+}
+
+/// Location of the configuration file read at startup, if the home directory is known
+fn default_config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("chip8-sdl.conf"))
+}
+
+/// Read the options of the configuration file at `path`, as command line arguments, see
+/// [`parse_config`]<br>
+/// `configs` holds the files read so far, which are not read again so that files including each
+/// other with `config` are rejected rather than read forever
+pub(crate) fn read_config(
+    path: &str,
+    configs: &mut HashSet<PathBuf>,
+) -> Result<VecDeque<String>, String> {
+    let config =
+        fs::read_to_string(path).map_err(|e| format!("Could not read config `{path}`: {e}"))?;
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    if !configs.insert(canonical) {
+        return Err(format!("Config `{path}` is included more than once"));
+    }
+    Ok(parse_config(&config))
+}
+
+/// Parse the options of a configuration file, as command line arguments<br>
+/// Each line holds `option = value` or `option`, without the leading dashes; empty lines and lines
+/// starting with `#` are ignored, and values may be quoted with `"` or `'` to keep their surrounding
+/// spaces
+pub(crate) fn parse_config(config: &str) -> VecDeque<String> {
+    let mut args = VecDeque::new();
+    for line in config.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((option, value)) => {
+                let value = value.trim();
+                let unquoted = ['"', '\'']
+                    .into_iter()
+                    .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote));
+                args.push_back(format!("--{}", option.trim()));
+                args.push_back(unquoted.unwrap_or(value).to_owned());
+            }
+            None => args.push_back(format!("--{line}")),
+        }
+    }
+    args
+}
+
+/// Parse a stack depth, `unlimited` meaning no limit
+fn parse_stack_size(s: &str) -> Result<Option<usize>, String> {
+    match s {
+        "unlimited" => Ok(None),
+        _ => s
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid stack size `{s}`")),
+    }
+}
+
+/// Parse a size of the pixels in the window, from 1 to [`MAX_SCALE`]
+pub(crate) fn parse_scale(s: &str) -> Result<u32, String> {
+    match parse_option("--scale", s)? {
+        scale @ 1..=MAX_SCALE => Ok(scale),
+        _ => Err(format!("The scale must be between 1 and {MAX_SCALE}")),
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal number
+fn parse_u16(s: &str) -> Result<u16, String> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("Invalid number `{s}`"))
+}
+
+/// Create a COSMAC VIP random number generator from the page at 0x100 of the interpreter dump at
+/// `path`, its pointer starting at `seed`
+fn load_vip_random(path: &str, seed: u64) -> Result<VipRandom, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read interpreter `{path}`: {e}"))?;
+    let page = bytes
+        .get(0x100..0x200)
+        .and_then(|page| page.try_into().ok())
+        .ok_or_else(|| format!("Interpreter `{path}` is shorter than 512 bytes"))?;
+    let seed = u8::try_from(seed).map_err(|_| format!("Invalid VIP seed `{seed}`"))?;
+    Ok(VipRandom::new(page, seed))
+}
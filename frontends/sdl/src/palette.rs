@@ -0,0 +1,88 @@
+//! Colours of the display
+
+use chip8::frontend::MONO_COLORS;
+
+/// Colours of the display, indexed by the bitplanes a pixel is lit on: background, first plane,
+/// second plane and both planes<br>
+/// CHIP-8 and SUPER-CHIP only use the first two, MEGA-CHIP brings its own colours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Name of the preset, or `custom`
+    pub name: &'static str,
+
+    /// RGB colours
+    pub colors: [u32; 4],
+}
+
+impl Palette {
+    /// White on black, with greys for the second plane
+    pub const MONO: Self = Self {
+        name: "mono",
+        colors: MONO_COLORS,
+    };
+
+    /// Default colours of Octo
+    pub const OCTO: Self = Self {
+        name: "octo",
+        colors: [0x996600, 0xFFCC00, 0xFF6600, 0x662200],
+    };
+
+    /// Amber monochrome monitor
+    pub const AMBER: Self = Self {
+        name: "amber",
+        colors: [0x1A1000, 0xFFB000, 0x996A00, 0xFFD780],
+    };
+
+    /// Green phosphor monochrome monitor
+    pub const GREEN: Self = Self {
+        name: "green",
+        colors: [0x0A1A0A, 0x33FF33, 0x1F991F, 0xAAFFAA],
+    };
+
+    /// Greenish liquid crystal display
+    pub const LCD: Self = Self {
+        name: "lcd",
+        colors: [0x9BBC0F, 0x0F380F, 0x306230, 0x8BAC0F],
+    };
+
+    /// Saturated colours on black
+    pub const HIGH_CONTRAST: Self = Self {
+        name: "high-contrast",
+        colors: [0x000000, 0xFFFF00, 0x00FFFF, 0xFFFFFF],
+    };
+
+    /// Every preset, in the order the hotkey cycles through them
+    pub const PRESETS: [Self; 6] = [
+        Self::MONO,
+        Self::OCTO,
+        Self::AMBER,
+        Self::GREEN,
+        Self::LCD,
+        Self::HIGH_CONTRAST,
+    ];
+
+    /// Preset called `name`
+    pub fn preset(name: &str) -> Option<Self> {
+        Self::PRESETS.into_iter().find(|preset| preset.name == name)
+    }
+
+    /// Opaque ARGB colour of a pixel lit on the bitplanes `planes`
+    pub fn argb(&self, planes: u8) -> u32 {
+        0xFF00_0000 | self.colors[planes as usize]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::MONO
+    }
+}
+
+/// Parse an RGB colour written `RRGGBB` or `#RRGGBB`
+pub fn parse_color(s: &str) -> Result<u32, String> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    match u32::from_str_radix(hex, 16) {
+        Ok(color) if hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()) => Ok(color),
+        _ => Err(format!("Invalid colour `{s}`, expected RRGGBB")),
+    }
+}
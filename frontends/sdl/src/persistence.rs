@@ -0,0 +1,99 @@
+//! Flicker reduction, hiding the pixels that XOR drawing turns off for a frame or two
+
+use std::collections::VecDeque;
+
+/// How the previous frames persist on the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Persistence {
+    /// Only the current frame is shown
+    #[default]
+    Off,
+
+    /// Like a phosphor, a pixel turned off fades to the background over this number of frames
+    Phosphor(u32),
+
+    /// Each pixel shows the average of its colours in this number of last frames
+    Blend(usize),
+}
+
+/// Display filter applying a [`Persistence`] to successive frames<br>
+/// It only changes what is shown, never the state of the machine
+#[derive(Debug, Default)]
+pub struct PersistenceFilter {
+    /// How the previous frames persist
+    mode: Persistence,
+
+    /// Number of pixels of the frames, the filter restarting when it changes
+    len: usize,
+
+    /// Last lit colour of each pixel, and the number of frames since it turned off
+    fading: Vec<(u32, u32)>,
+
+    /// Last frames, most recent last
+    history: VecDeque<Vec<u32>>,
+}
+
+impl PersistenceFilter {
+    /// Filter applying `mode`
+    pub fn new(mode: Persistence) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Apply the filter to `frame`, a buffer of opaque ARGB pixels whose unlit pixels are
+    /// `background`
+    pub fn apply(&mut self, frame: &mut [u32], background: u32) {
+        if frame.len() != self.len {
+            self.len = frame.len();
+            self.fading = vec![(background, u32::MAX); frame.len()];
+            self.history.clear();
+        }
+
+        match self.mode {
+            Persistence::Off => {}
+            Persistence::Phosphor(frames) => {
+                for (pixel, (lit, age)) in frame.iter_mut().zip(&mut self.fading) {
+                    if *pixel != background {
+                        (*lit, *age) = (*pixel, 0);
+                    } else {
+                        *age = age.saturating_add(1);
+                        if *age < frames {
+                            *pixel = mix(*lit, background, *age, frames);
+                        }
+                    }
+                }
+            }
+            Persistence::Blend(frames) => {
+                self.history.push_back(frame.to_vec());
+                while self.history.len() > frames.max(1) {
+                    self.history.pop_front();
+                }
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    *pixel = average(self.history.iter().map(|past| past[i]));
+                }
+            }
+        }
+    }
+}
+
+/// Colour `step` steps of `steps` of the way from `from` to `to`
+fn mix(from: u32, to: u32, step: u32, steps: u32) -> u32 {
+    let channel = |shift: u32| {
+        let (from, to) = ((from >> shift) & 0xFF, (to >> shift) & 0xFF);
+        let value = (from * (steps - step) + to * step) / steps;
+        value << shift
+    };
+    0xFF00_0000 | channel(16) | channel(8) | channel(0)
+}
+
+/// Average of opaque ARGB `colors`
+fn average(colors: impl ExactSizeIterator<Item = u32> + Clone) -> u32 {
+    let count = colors.len().max(1) as u32;
+    let channel = |shift: u32| {
+        let sum: u32 = colors.clone().map(|color| (color >> shift) & 0xFF).sum();
+        (sum / count) << shift
+    };
+    0xFF00_0000 | channel(16) | channel(8) | channel(0)
+}
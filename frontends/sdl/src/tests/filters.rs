@@ -0,0 +1,138 @@
+//! Post-processing of the display
+
+use crate::filters::{Effect, Filters, Image, Upscaler};
+
+const BLACK: u32 = 0xFF00_0000;
+const WHITE: u32 = 0xFFFF_FFFF;
+
+/// Image drawn with `#` for white pixels and `.` for black ones, row by row
+fn image(rows: &[&str]) -> Image {
+    Image {
+        width: rows[0].len(),
+        height: rows.len(),
+        pixels: rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| if c == '#' { WHITE } else { BLACK })
+            .collect(),
+    }
+}
+
+/// `image` through `filters`, named as on the command line
+fn apply(filters: &[&str], image: Image) -> Image {
+    let mut selection = Filters::default();
+    for name in filters {
+        selection.add(name).unwrap();
+    }
+    selection.apply(image)
+}
+
+/// Diagonal line, from the top left corner to the bottom right one
+fn diagonal() -> Image {
+    image(&["#..", ".#.", "..#"])
+}
+
+#[test]
+fn scale2x_smooths_diagonal() {
+    #[rustfmt::skip]
+    let expected = image(&[
+        "##....",
+        "#.#...",
+        ".###..",
+        "..###.",
+        "...#.#",
+        "....##",
+    ]);
+    assert_eq!(apply(&["scale2x"], diagonal()), expected);
+}
+
+#[test]
+fn scale3x_smooths_diagonal() {
+    #[rustfmt::skip]
+    let expected = image(&[
+        "###......",
+        "##.#.....",
+        "#..#.....",
+        ".#####...",
+        "...###...",
+        "...#####.",
+        ".....#..#",
+        ".....#.##",
+        "......###",
+    ]);
+    assert_eq!(apply(&["scale3x"], diagonal()), expected);
+}
+
+#[test]
+fn epx_matches_scale2x() {
+    let stripes = image(&["#.#.", "##..", "..##", ".#.#"]);
+    for image in [diagonal(), stripes] {
+        assert_eq!(apply(&["epx"], image.clone()), apply(&["scale2x"], image));
+    }
+}
+
+#[test]
+fn no_filters_is_pass_through() {
+    assert_eq!(apply(&[], diagonal()), diagonal());
+}
+
+#[test]
+fn scanlines_darken_last_row_of_each_pixel() {
+    let dark = 0xFF7F_7F7F;
+    let scaled = apply(&["scanlines"], image(&["##"]));
+    assert_eq!((scaled.width, scaled.height), (6, 3));
+    assert_eq!(scaled.pixels, [[WHITE; 6], [WHITE; 6], [dark; 6]].concat());
+
+    let scaled = apply(&["scale2x", "scanlines"], image(&["#"]));
+    assert_eq!(scaled.pixels, [WHITE, WHITE, dark, dark]);
+}
+
+#[test]
+fn grid_darkens_edges_of_each_pixel() {
+    let dark = 0xFFBF_BFBF;
+    let row = [WHITE, WHITE, dark, WHITE, WHITE, dark];
+    let scaled = apply(&["grid"], image(&["##"]));
+    assert_eq!((scaled.width, scaled.height), (6, 3));
+    assert_eq!(scaled.pixels, [row, row, [dark; 6]].concat());
+}
+
+#[test]
+fn glow_spreads_light_around_lit_pixels() {
+    let scaled = apply(&["glow"], image(&["#.."]));
+    assert_eq!((scaled.width, scaled.height), (9, 3));
+    assert!(scaled.pixels[..3].iter().all(|&pixel| pixel == WHITE));
+    assert!(scaled.pixels[3] > BLACK && scaled.pixels[3] < WHITE);
+    assert_eq!(scaled.pixels[8], BLACK);
+}
+
+#[test]
+fn filters_parse_in_order() {
+    let mut filters = Filters::default();
+    assert!(filters.is_empty());
+    for name in ["grid", "scale3x", "scanlines", "glow"] {
+        filters.add(name).unwrap();
+    }
+    assert_eq!(
+        filters,
+        Filters {
+            upscaler: Some(Upscaler::Scale3x),
+            effects: vec![Effect::Grid, Effect::Scanlines, Effect::Glow],
+        }
+    );
+}
+
+#[test]
+fn only_one_upscaler() {
+    let mut filters = Filters::default();
+    filters.add("epx").unwrap();
+    assert!(filters.add("scale2x").is_err());
+    assert_eq!(filters.upscaler, Some(Upscaler::Epx));
+}
+
+#[test]
+fn unknown_filter() {
+    let mut filters = Filters::default();
+    assert!(filters.add("hq2x").is_err());
+    assert!(filters.add("Scale2x").is_err());
+    assert!(filters.is_empty());
+}
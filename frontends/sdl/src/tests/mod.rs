@@ -0,0 +1,4 @@
+mod filters;
+mod options;
+mod palette;
+mod persistence;
@@ -0,0 +1,81 @@
+//! Command line options and configuration files
+
+use crate::options::{parse_colors, parse_config, parse_scale, read_config};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Write a configuration file called `name` in a directory of its own, and return its path
+fn write_config(name: &str, config: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("chip8-sdl-tests-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, config).unwrap();
+    path
+}
+
+#[test]
+fn config_options_and_flags() {
+    let config = "palette = amber\nfullscreen\n  scale=4  \n";
+    assert_eq!(
+        parse_config(config),
+        ["--palette", "amber", "--fullscreen", "--scale", "4"]
+    );
+}
+
+#[test]
+fn config_comments_and_blank_lines() {
+    let config = "# Colours\n\n   \n  # indented comment\nfg = #FFB000\n\n";
+    assert_eq!(parse_config(config), ["--fg", "#FFB000"]);
+}
+
+#[test]
+fn config_quoted_values() {
+    let config = "font = \"  my font.bin \"\nrom = 'a=b'\nbg = \"\nfg = \"FFB000'";
+    assert_eq!(
+        parse_config(config),
+        [
+            "--font",
+            "  my font.bin ",
+            "--rom",
+            "a=b",
+            "--bg",
+            "\"",
+            "--fg",
+            "\"FFB000'"
+        ]
+    );
+}
+
+#[test]
+fn config_including_itself_is_rejected() {
+    let path = write_config("self.conf", "scale = 2\n");
+    let path = path.to_string_lossy();
+    let mut configs = HashSet::new();
+
+    assert_eq!(
+        read_config(&path, &mut configs).map(Vec::from),
+        Ok(vec!["--scale".to_owned(), "2".to_owned()])
+    );
+    assert!(read_config(&path, &mut configs).is_err());
+}
+
+#[test]
+fn colors_option() {
+    assert_eq!(
+        parse_colors("000000, FFFFFF,#AAAAAA,555555"),
+        Ok([0x000000, 0xFFFFFF, 0xAAAAAA, 0x555555])
+    );
+    assert!(parse_colors("000000,FFFFFF,AAAAAA").is_err());
+    assert!(parse_colors("000000,FFFFFF,AAAAAA,555555,FFFFFF").is_err());
+    assert!(parse_colors("000000,FFFFFF,AAAAAA,nope").is_err());
+}
+
+#[test]
+fn scale_is_bounded() {
+    assert_eq!(parse_scale("1"), Ok(1));
+    assert_eq!(parse_scale("64"), Ok(64));
+    for scale in ["0", "65", "4294967295"] {
+        assert!(parse_scale(scale).is_err(), "{scale}");
+    }
+}
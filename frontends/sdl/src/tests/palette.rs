@@ -0,0 +1,29 @@
+//! Colours of the display
+
+use crate::palette::{parse_color, Palette};
+
+#[test]
+fn parse_colors() {
+    assert_eq!(parse_color("FFB000"), Ok(0xFFB000));
+    assert_eq!(parse_color("#0a1a0a"), Ok(0x0A1A0A));
+    for s in ["", "#", "FFF", "#FFB0000", "FFB00G", "##FFB000", "+FFFFF"] {
+        assert!(parse_color(s).is_err(), "{s}");
+    }
+}
+
+#[test]
+fn presets() {
+    assert_eq!(Palette::default(), Palette::MONO);
+    for preset in Palette::PRESETS {
+        assert_eq!(Palette::preset(preset.name), Some(preset));
+    }
+    assert_eq!(Palette::preset("custom"), None);
+    assert_eq!(Palette::preset("Amber"), None);
+}
+
+#[test]
+fn argb_is_opaque() {
+    assert_eq!(Palette::AMBER.argb(0), 0xFF1A_1000);
+    assert_eq!(Palette::AMBER.argb(1), 0xFFFF_B000);
+    assert_eq!(Palette::AMBER.argb(3), 0xFFFF_D780);
+}
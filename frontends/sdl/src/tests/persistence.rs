@@ -0,0 +1,106 @@
+//! Flicker reduction
+
+use crate::persistence::{Persistence, PersistenceFilter};
+
+const BLACK: u32 = 0xFF00_0000;
+const WHITE: u32 = 0xFFFF_FFFF;
+
+/// Frames of two pixels shown through a filter applying `mode`
+fn filter(mode: Persistence, frames: &[[u32; 2]]) -> Vec<[u32; 2]> {
+    let mut filter = PersistenceFilter::new(mode);
+    frames
+        .iter()
+        .map(|&frame| {
+            let mut frame = frame;
+            filter.apply(&mut frame, BLACK);
+            frame
+        })
+        .collect()
+}
+
+#[test]
+fn phosphor_decays_to_background() {
+    let frames = [
+        [WHITE, BLACK],
+        [BLACK, BLACK],
+        [BLACK, BLACK],
+        [BLACK, BLACK],
+        [BLACK, BLACK],
+    ];
+    assert_eq!(
+        filter(Persistence::Phosphor(4), &frames),
+        [
+            [WHITE, BLACK],
+            [0xFFBF_BFBF, BLACK],
+            [0xFF7F_7F7F, BLACK],
+            [0xFF3F_3F3F, BLACK],
+            [BLACK, BLACK],
+        ]
+    );
+}
+
+#[test]
+fn phosphor_restarts_when_lit_again() {
+    let frames = [
+        [WHITE, BLACK],
+        [BLACK, BLACK],
+        [WHITE, BLACK],
+        [BLACK, BLACK],
+    ];
+    assert_eq!(
+        filter(Persistence::Phosphor(2), &frames),
+        [
+            [WHITE, BLACK],
+            [0xFF7F_7F7F, BLACK],
+            [WHITE, BLACK],
+            [0xFF7F_7F7F, BLACK]
+        ]
+    );
+}
+
+#[test]
+fn no_frames_is_pass_through() {
+    let frames = [
+        [WHITE, BLACK],
+        [BLACK, WHITE],
+        [BLACK, BLACK],
+        [WHITE, WHITE],
+    ];
+    for mode in [
+        Persistence::Off,
+        Persistence::Phosphor(0),
+        Persistence::Phosphor(1),
+        Persistence::Blend(0),
+        Persistence::Blend(1),
+    ] {
+        assert_eq!(filter(mode, &frames), frames, "{mode:?}");
+    }
+}
+
+#[test]
+fn blend_averages_last_frames() {
+    let frames = [
+        [WHITE, BLACK],
+        [BLACK, BLACK],
+        [BLACK, WHITE],
+        [BLACK, WHITE],
+    ];
+    assert_eq!(
+        filter(Persistence::Blend(2), &frames),
+        [
+            [WHITE, BLACK],
+            [0xFF7F_7F7F, BLACK],
+            [BLACK, 0xFF7F_7F7F],
+            [BLACK, WHITE],
+        ]
+    );
+}
+
+#[test]
+fn size_change_restarts_filter() {
+    let mut filter = PersistenceFilter::new(Persistence::Blend(2));
+    filter.apply(&mut [WHITE, WHITE], BLACK);
+    let mut frame = [BLACK, BLACK, BLACK];
+    filter.apply(&mut frame, BLACK);
+    assert_eq!(frame, [BLACK; 3]);
+}
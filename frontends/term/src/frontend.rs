@@ -0,0 +1,306 @@
+//! Terminal hosting the emulator: the display drawn with Unicode characters, the keyboard mapped to
+//! the keypad and the bell as beeper
+
+use crate::terminal::RawTerminal;
+use chip8::frontend::{Frontend, KEYPAD_LAYOUT, MONO_COLORS};
+use chip8::{Chip8, Chip8Error, KeyState, KEYS_COUNT};
+use std::fmt::Write;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Keys of a QWERTY keyboard mapped to the keypad, laid out as in [`KEYPAD_LAYOUT`]
+const KEYS: [[u8; 4]; 4] = [*b"1234", *b"qwer", *b"asdf", *b"zxcv"];
+
+/// Byte sent by Ctrl-C, which quits
+const CTRL_C: u8 = 0x03;
+
+/// Byte sent by Escape, which quits, and starting the escape sequences of the other special keys
+const ESCAPE: u8 = 0x1B;
+
+/// Bit of each dot of a braille character, indexed by its row then its column
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// How pixels are packed into the characters of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Glyphs {
+    /// Two pixels, one above the other, per half-block character, in colour
+    HalfBlocks,
+
+    /// Eight pixels, two columns of four, per braille character, lit or not
+    Braille,
+}
+
+impl Glyphs {
+    /// Half blocks if a display of `width` by `height` pixels fits in `columns` and `rows` with them,
+    /// braille otherwise
+    pub(crate) fn fitting(width: usize, height: usize, columns: usize, rows: usize) -> Self {
+        if width <= columns && height.div_ceil(2) <= rows {
+            Self::HalfBlocks
+        } else {
+            Self::Braille
+        }
+    }
+
+    /// Size of the pixel area covered by a character, in columns and rows
+    fn cell_size(self) -> (usize, usize) {
+        match self {
+            Self::HalfBlocks => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+}
+
+/// Keys typed in the terminal, mapped to the keypad<br>
+/// Terminals send no event when keys are released, so keys are considered released once they have
+/// not been repeated for a while
+#[derive(Debug)]
+pub(crate) struct Keyboard {
+    /// Time after which a key that is no longer repeated is released
+    release_timeout: Duration,
+
+    /// When each held key was last received
+    held: [Option<Instant>; KEYS_COUNT],
+}
+
+impl Keyboard {
+    /// Keyboard releasing keys that have not been repeated for `release_timeout`
+    pub(crate) fn new(release_timeout: Duration) -> Self {
+        Self {
+            release_timeout,
+            held: [None; KEYS_COUNT],
+        }
+    }
+
+    /// Update the held keys with the bytes of `input`, received at `now`, and return the key events
+    /// of the frame<br>
+    /// `None` means that the user asked to quit
+    pub(crate) fn update(
+        &mut self,
+        input: &[u8],
+        now: Instant,
+    ) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+        let mut frame_keys: [Option<KeyState>; KEYS_COUNT] = Default::default();
+        let mut bytes = input.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                CTRL_C => return None,
+                // Skip the escape sequences of the arrows, function keys...
+                ESCAPE if matches!(bytes.peek(), Some(b'[' | b'O')) => {
+                    bytes.next();
+                    for byte in bytes.by_ref() {
+                        if (0x40..=0x7E).contains(&byte) {
+                            break;
+                        }
+                    }
+                }
+                ESCAPE => return None,
+                _ => {
+                    let key = byte.to_ascii_lowercase();
+                    if let Some(i) = keypad_key(key) {
+                        // Key repeats only keep the key held
+                        if self.held[i].is_none() {
+                            frame_keys[i] = Some(KeyState::Pressed);
+                        }
+                        self.held[i] = Some(now);
+                    }
+                }
+            }
+        }
+
+        for (i, held) in self.held.iter_mut().enumerate() {
+            if held.is_some_and(|since| now - since >= self.release_timeout) {
+                *held = None;
+                frame_keys[i] = Some(KeyState::Released);
+            }
+        }
+        Some(frame_keys)
+    }
+}
+
+/// Frame last written to the terminal, so that unchanged frames are not written again
+#[derive(Debug, Default)]
+pub(crate) struct LastFrame {
+    /// Size of the terminal, in columns and rows
+    size: (usize, usize),
+
+    /// Text written to draw the display
+    frame: String,
+}
+
+impl LastFrame {
+    /// Text to write to draw the display of `chip8` on a terminal of `size` columns and rows,
+    /// clearing the terminal first if its size changed<br>
+    /// `None` means that the terminal already shows this frame
+    pub(crate) fn update(&mut self, chip8: &Chip8, size: (usize, usize)) -> Option<String> {
+        let frame = TermFrontend::render(chip8, size.0, size.1);
+        if size == self.size && frame == self.frame {
+            return None;
+        }
+
+        let mut output = String::new();
+        if size != self.size {
+            output.push_str("\x1b[0m\x1b[2J");
+            self.size = size;
+        }
+        output += &frame;
+        self.frame = frame;
+        Some(output)
+    }
+
+    /// Forget the frame, so that the next one is written even if it is unchanged
+    pub(crate) fn forget(&mut self) {
+        self.frame.clear();
+    }
+}
+
+/// Terminal showing the display, keyboard mapped to the keypad and bell ringing when the beeper
+/// turns on
+pub struct TermFrontend {
+    /// Terminal in raw mode
+    terminal: RawTerminal,
+
+    /// Keys held on the keyboard
+    keyboard: Keyboard,
+
+    /// Frame last written to the terminal
+    last_frame: LastFrame,
+
+    /// The beeper is on
+    beeping: bool,
+
+    /// Why the machine halted, to report once the terminal is restored
+    halt_message: Option<String>,
+}
+
+impl TermFrontend {
+    /// Take over the terminal, releasing keys that have not been repeated for `release_timeout`
+    pub fn new(release_timeout: Duration) -> io::Result<Self> {
+        Ok(Self {
+            terminal: RawTerminal::enter()?,
+            keyboard: Keyboard::new(release_timeout),
+            last_frame: LastFrame::default(),
+            beeping: false,
+            halt_message: None,
+        })
+    }
+
+    /// Restore the terminal and return why the machine halted, if it did because of an error
+    pub fn close(self) -> Option<String> {
+        self.halt_message
+    }
+
+    /// Draw the display of `chip8`, centred and cropped to the `columns` and `rows` of the terminal
+    pub(crate) fn render(chip8: &Chip8, columns: usize, rows: usize) -> String {
+        let (width, height) = (chip8.screen_width(), chip8.screen_height());
+        let glyphs = Glyphs::fitting(width, height, columns, rows);
+        let (cell_width, cell_height) = glyphs.cell_size();
+        let (cells_x, cells_y) = (width.div_ceil(cell_width), height.div_ceil(cell_height));
+        let (left, top) = (
+            columns.saturating_sub(cells_x) / 2,
+            rows.saturating_sub(cells_y) / 2,
+        );
+
+        let color = |x: usize, y: usize| -> Option<u32> {
+            (x < width && y < height).then(|| match chip8.screen_colors() {
+                // MEGA-CHIP mode, pixels are ARGB
+                Some(colors) => colors[x + y * width] & 0xFF_FFFF,
+                None => MONO_COLORS[chip8.screen()[x + y * width] as usize],
+            })
+        };
+        let lit = |x: usize, y: usize| color(x, y).is_some_and(|color| color != 0);
+
+        let mut frame = String::new();
+        for cell_y in 0..cells_y.min(rows) {
+            let _ = write!(frame, "\x1b[{};{}H", top + cell_y + 1, left + 1);
+            let mut last_colors = None;
+            for cell_x in 0..cells_x.min(columns) {
+                let (x, y) = (cell_x * cell_width, cell_y * cell_height);
+                match glyphs {
+                    Glyphs::HalfBlocks => {
+                        let colors = (color(x, y).unwrap_or(0), color(x, y + 1).unwrap_or(0));
+                        if last_colors != Some(colors) {
+                            let (fg, bg) = colors;
+                            let _ = write!(
+                                frame,
+                                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+                                fg >> 16,
+                                (fg >> 8) & 0xFF,
+                                fg & 0xFF,
+                                bg >> 16,
+                                (bg >> 8) & 0xFF,
+                                bg & 0xFF
+                            );
+                            last_colors = Some(colors);
+                        }
+                        frame.push('▀');
+                    }
+                    Glyphs::Braille => {
+                        let mut dots = 0;
+                        for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+                            for (dx, bit) in row.iter().enumerate() {
+                                if lit(x + dx, y + dy) {
+                                    dots |= bit;
+                                }
+                            }
+                        }
+                        frame.push(char::from_u32(0x2800 + dots).unwrap_or(' '));
+                    }
+                }
+            }
+            frame.push_str("\x1b[0m");
+        }
+        frame
+    }
+}
+
+impl Frontend for TermFrontend {
+    fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+        let input = match self.terminal.read_input() {
+            Ok(input) => input,
+            Err(e) => {
+                self.halt_message = Some(format!("Could not read the keyboard: {e}"));
+                return None;
+            }
+        };
+
+        self.keyboard.update(&input, Instant::now())
+    }
+
+    fn present(&mut self, chip8: &Chip8) {
+        // Keep the last size if the terminal can't tell, for instance when output is redirected
+        let size = self.terminal.size().unwrap_or(self.last_frame.size);
+        if let Some(frame) = self.last_frame.update(chip8, size) {
+            // A frame that can't be written is skipped, and written again at the next frame
+            if self.terminal.write(&frame).is_err() {
+                self.last_frame.forget();
+            }
+        }
+    }
+
+    fn set_beeper(&mut self, on: bool) {
+        if on && !self.beeping {
+            let _ = self.terminal.write("\x07");
+        }
+        self.beeping = on;
+    }
+
+    fn halted(&mut self, chip8: &Chip8, error: Option<&Chip8Error>) {
+        // Keep showing the last frame until the user quits
+        if let Some(e) = error {
+            let mut message = format!("Emulation halted: {e}");
+            if let Some(seed) = chip8.random_seed() {
+                let _ = write!(message, "\nRandom seed: {seed}");
+            }
+            self.halt_message = Some(message);
+        }
+    }
+}
+
+/// Key of the keypad mapped to `key`, a lowercase character of the keyboard
+fn keypad_key(key: u8) -> Option<usize> {
+    KEYS.iter()
+        .flatten()
+        .zip(KEYPAD_LAYOUT.iter().flatten())
+        .find(|&(&k, _)| k == key)
+        .map(|(_, &i)| i)
+}
@@ -0,0 +1,56 @@
+mod frontend;
+mod options;
+mod terminal;
+
+#[cfg(test)]
+mod tests;
+
+use chip8::frontend::Driver;
+use chip8::Chip8;
+use frontend::TermFrontend;
+use options::{usage, Options};
+use std::{env, fs, process};
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", usage());
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let path = &options.rom;
+    let rom = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Could not read ROM `{path}`: {e}");
+        process::exit(1);
+    });
+
+    let mut chip8 = Chip8::with_platform(options.platform);
+    chip8.set_quirks(options.quirks);
+    chip8.set_clock_hz(options.clock_hz);
+    if let Some(seed) = options.seed {
+        chip8.set_random_seed(seed);
+    }
+    if let Err(e) = chip8.load_cartridge(&rom) {
+        eprintln!("Could not load ROM `{path}`: {e}");
+        process::exit(1);
+    }
+
+    let frontend = TermFrontend::new(options.key_timeout).unwrap_or_else(|e| {
+        eprintln!("Could not set up the terminal: {e}");
+        process::exit(1);
+    });
+    let mut driver = Driver::new(chip8, frontend);
+    driver.run();
+
+    // The terminal is restored once the frontend is closed
+    if let Some(message) = driver.into_parts().1.close() {
+        eprintln!("{message}");
+        process::exit(1);
+    }
+}
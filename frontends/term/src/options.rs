@@ -0,0 +1,100 @@
+//! Command line options
+
+use chip8::frontend::parse_option;
+use chip8::{Platform, Quirks, DEFAULT_CLOCK_HZ};
+use std::time::Duration;
+
+/// Help printed by `--help` and after invalid arguments
+pub fn usage() -> String {
+    let platforms = Platform::ALL.map(Platform::name).join(", ");
+    let quirks = Quirks::PRESETS.map(|(name, _)| name).join(", ");
+    format!(
+        "\
+Usage: chip8-term [OPTIONS] <ROM>
+
+Keys 1-4, Q-R, A-F and Z-V form the keypad. Press Escape or Ctrl-C to quit.
+
+Options:
+    --speed <HZ>          Instructions executed per second (default: 700)
+    --platform <NAME>     Machine to emulate: {platforms} (default: chip8)
+    --quirks <PRESET>     Interpreter to behave like: {quirks} (default: the platform's)
+    --seed <N>            Seed of the random numbers, to reproduce a session (default: random)
+    --key-timeout <MS>    Time after which a key that is no longer repeated is released (default: 600)
+                          Terminals only report key repeats, which start after the repeat delay of the
+                          system (usually about 500 ms), so shorter timeouts release held keys before
+                          they repeat; longer ones make released keys linger
+    -h, --help            Print this message"
+    )
+}
+
+/// Default time after which a key that is no longer repeated is released, in milliseconds<br>
+/// It must exceed the usual delay before keys start repeating, about 500 ms, or held keys would be
+/// released and pressed again
+const DEFAULT_KEY_TIMEOUT_MS: u64 = 600;
+
+/// Settings of an emulation session
+pub struct Options {
+    /// Path of the ROM
+    pub rom: String,
+
+    /// Speed of the CPU, in instructions per second
+    pub clock_hz: u32,
+
+    /// Behaviours that differ between interpreters
+    pub quirks: Quirks,
+
+    /// Machine to emulate
+    pub platform: Platform,
+
+    /// Seed of the random numbers, random if `None`
+    pub seed: Option<u64>,
+
+    /// Time after which a key that is no longer repeated is released
+    pub key_timeout: Duration,
+}
+
+impl Options {
+    /// Parse the command line arguments, without the program name<br>
+    /// `Ok(None)` means that the help was requested
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut rom = None;
+        let mut clock_hz = DEFAULT_CLOCK_HZ;
+        let mut quirks = None;
+        let mut platform = Platform::default();
+        let mut seed = None;
+        let mut key_timeout_ms = DEFAULT_KEY_TIMEOUT_MS;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for `{name}`"))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--speed" => clock_hz = parse_option("--speed", &value("--speed")?)?,
+                "--quirks" => quirks = Some(parse_option("--quirks", &value("--quirks")?)?),
+                "--platform" => platform = parse_option("--platform", &value("--platform")?)?,
+                "--seed" => seed = Some(parse_option("--seed", &value("--seed")?)?),
+                "--key-timeout" => {
+                    key_timeout_ms = parse_option("--key-timeout", &value("--key-timeout")?)?
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown option `{arg}`")),
+                _ if rom.is_none() => rom = Some(arg),
+                _ => return Err(format!("Unexpected argument `{arg}`")),
+            }
+        }
+
+        let rom = rom.ok_or("Missing ROM")?;
+        let quirks = quirks.unwrap_or(platform.spec().quirks);
+        Ok(Some(Self {
+            rom,
+            clock_hz,
+            quirks,
+            platform,
+            seed,
+            key_timeout: Duration::from_millis(key_timeout_ms),
+        }))
+    }
+}
@@ -0,0 +1,86 @@
+//! Raw mode, size and input of the controlling terminal
+
+use std::io::{self, Write};
+use std::mem;
+
+/// Switch to the alternate screen, hide the cursor and clear the screen
+const ENTER: &str = "\x1b[?1049h\x1b[?25l\x1b[2J";
+
+/// Reset the colours, show the cursor and leave the alternate screen
+const LEAVE: &str = "\x1b[0m\x1b[?25h\x1b[?1049l";
+
+/// Terminal in raw mode, showing the alternate screen, restored when dropped
+pub struct RawTerminal {
+    /// Settings of the terminal before raw mode
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    /// Put the terminal in raw mode, with non-blocking reads, and switch to the alternate screen
+    pub fn enter() -> io::Result<Self> {
+        // SAFETY: `termios` is plain data, filled in by `tcgetattr`
+        let mut original: libc::termios = unsafe { mem::zeroed() };
+        // SAFETY: `original` is a valid `termios` to write to
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Read bytes as they are typed, without echo or signals, and without waiting for any
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: `raw` is a valid `termios`
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let terminal = Self { original };
+        terminal.write(ENTER)?;
+        Ok(terminal)
+    }
+
+    /// Size of the terminal, in columns and rows
+    pub fn size(&self) -> io::Result<(usize, usize)> {
+        // SAFETY: `winsize` is plain data, filled in by `ioctl`
+        let mut size: libc::winsize = unsafe { mem::zeroed() };
+        // SAFETY: `TIOCGWINSZ` writes a `winsize` to `size`
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((size.ws_col as usize, size.ws_row as usize))
+    }
+
+    /// Bytes typed since the last call, without waiting
+    pub fn read_input(&self) -> io::Result<Vec<u8>> {
+        let mut input = Vec::new();
+        let mut buf = [0; 64];
+        loop {
+            // SAFETY: `buf` is valid for writes of `buf.len()` bytes
+            let read =
+                unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+            match read {
+                0 => return Ok(input),
+                n if n < 0 => return Err(io::Error::last_os_error()),
+                n => input.extend_from_slice(&buf[..n as usize]),
+            }
+        }
+    }
+
+    /// Write `text` to the terminal at once
+    pub fn write(&self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        // Nothing more can be done if the terminal can't be restored
+        let _ = self.write(LEAVE);
+        // SAFETY: `original` is the valid `termios` returned by `tcgetattr`
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
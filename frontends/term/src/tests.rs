@@ -0,0 +1,194 @@
+use crate::frontend::{Glyphs, Keyboard, LastFrame, TermFrontend};
+use chip8::{Chip8, KeyState, KEYS_COUNT};
+use std::time::{Duration, Instant};
+
+/// Time after which the test keyboards release keys
+const TIMEOUT: Duration = Duration::from_millis(600);
+
+/// Create a machine showing the glyph `0` of the font in the top left corner
+fn zero_machine() -> Chip8 {
+    // V0 = 0, I = font sprite of V0, draw 5 rows at (V0, V0)
+    let program: Vec<u8> = [0x6000u16, 0xF029, 0xD005]
+        .iter()
+        .flat_map(|op| op.to_be_bytes())
+        .collect();
+    let mut chip8 = Chip8::default();
+    chip8.load_cartridge(&program).unwrap();
+    for _ in 0..3 {
+        chip8.step().unwrap();
+    }
+    chip8
+}
+
+/// Split a frame into its rows of characters, without the escape sequences, half blocks being
+/// replaced by `█`, `▀`, `▄` or ` ` depending on which of their halves are lit
+fn glyph_rows(frame: &str) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut chars = frame.chars();
+    let mut colors = [0; 2];
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                // Control sequence: `[`, parameters separated by `;`, then a final letter
+                let sequence: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|&c| c != 'm' && c != 'H')
+                    .collect();
+                let fields: Vec<u32> = sequence.split(';').filter_map(|f| f.parse().ok()).collect();
+                match fields[..] {
+                    [layer @ (38 | 48), 2, r, g, b] => {
+                        colors[(layer == 48) as usize] = (r << 16) | (g << 8) | b
+                    }
+                    // Cursor moved to the start of a row
+                    [_, _] => rows.push(String::new()),
+                    _ => {}
+                }
+            }
+            '▀' => rows
+                .last_mut()
+                .unwrap()
+                .push(match colors.map(|color| color != 0) {
+                    [true, true] => '█',
+                    [true, false] => '▀',
+                    [false, true] => '▄',
+                    [false, false] => ' ',
+                }),
+            c => rows.last_mut().unwrap().push(c),
+        }
+    }
+    rows
+}
+
+/// Key events with `key` in `state` and the other keys unchanged
+fn only(key: usize, state: KeyState) -> [Option<KeyState>; KEYS_COUNT] {
+    let mut keys = [None; KEYS_COUNT];
+    keys[key] = Some(state);
+    keys
+}
+
+#[test]
+fn glyphs_fit_the_terminal() {
+    assert_eq!(Glyphs::fitting(64, 32, 80, 24), Glyphs::HalfBlocks);
+    assert_eq!(Glyphs::fitting(64, 32, 64, 16), Glyphs::HalfBlocks);
+    assert_eq!(Glyphs::fitting(64, 32, 63, 16), Glyphs::Braille);
+    assert_eq!(Glyphs::fitting(64, 32, 64, 15), Glyphs::Braille);
+    assert_eq!(Glyphs::fitting(128, 64, 80, 24), Glyphs::Braille);
+}
+
+#[test]
+fn render_half_blocks() {
+    let frame = TermFrontend::render(&zero_machine(), 64, 16);
+    let rows = glyph_rows(&frame);
+
+    assert_eq!(rows.len(), 16);
+    assert!(rows.iter().all(|row| row.chars().count() == 64));
+    let corner: Vec<String> = rows[..4]
+        .iter()
+        .map(|row| row.chars().take(5).collect())
+        .collect();
+    assert_eq!(corner, ["█▀▀█ ", "█  █ ", "▀▀▀▀ ", "     "]);
+}
+
+#[test]
+fn render_braille() {
+    let frame = TermFrontend::render(&zero_machine(), 32, 8);
+    let rows = glyph_rows(&frame);
+
+    assert_eq!(rows.len(), 8);
+    assert!(rows.iter().all(|row| row.chars().count() == 32));
+    let corner: Vec<String> = rows[..3]
+        .iter()
+        .map(|row| row.chars().take(3).collect())
+        .collect();
+    assert_eq!(corner, ["⡏⢹⠀", "⠉⠉⠀", "⠀⠀⠀"]);
+}
+
+#[test]
+fn render_is_centred_and_cropped() {
+    let frame = TermFrontend::render(&zero_machine(), 80, 20);
+    assert!(frame.starts_with("\x1b[3;9H"));
+
+    let rows = glyph_rows(&TermFrontend::render(&zero_machine(), 20, 4));
+    assert_eq!(rows.len(), 4);
+    assert!(rows.iter().all(|row| row.chars().count() == 20));
+    assert!(rows[0].starts_with("⡏⢹"));
+}
+
+#[test]
+fn unchanged_frames_are_not_written() {
+    let mut last_frame = LastFrame::default();
+    let chip8 = zero_machine();
+
+    let frame = last_frame.update(&chip8, (80, 24)).unwrap();
+    assert!(frame.starts_with("\x1b[0m\x1b[2J"));
+    assert_eq!(last_frame.update(&chip8, (80, 24)), None);
+
+    // A new size clears the terminal
+    let frame = last_frame.update(&chip8, (100, 30)).unwrap();
+    assert!(frame.starts_with("\x1b[0m\x1b[2J"));
+
+    // A new display is drawn over the previous one
+    let blank = Chip8::default();
+    let frame = last_frame.update(&blank, (100, 30)).unwrap();
+    assert!(!frame.starts_with("\x1b[0m\x1b[2J"));
+    assert!(glyph_rows(&frame).iter().all(|row| row.trim().is_empty()));
+
+    last_frame.forget();
+    assert!(last_frame.update(&blank, (100, 30)).is_some());
+}
+
+#[test]
+fn keys_are_mapped_to_the_keypad() {
+    let mut keyboard = Keyboard::new(TIMEOUT);
+    let now = Instant::now();
+
+    assert_eq!(
+        keyboard.update(b"x", now),
+        Some(only(0x0, KeyState::Pressed))
+    );
+    assert_eq!(
+        keyboard.update(b"W", now),
+        Some(only(0x5, KeyState::Pressed))
+    );
+    assert_eq!(
+        keyboard.update(b"v", now),
+        Some(only(0xF, KeyState::Pressed))
+    );
+    assert_eq!(keyboard.update(b"p", now), Some([None; KEYS_COUNT]));
+}
+
+#[test]
+fn keys_are_released_after_timeout() {
+    let mut keyboard = Keyboard::new(TIMEOUT);
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    assert_eq!(
+        keyboard.update(b"w", at(0)),
+        Some(only(0x5, KeyState::Pressed))
+    );
+    // Repeats keep the key held
+    assert_eq!(keyboard.update(b"", at(500)), Some([None; KEYS_COUNT]));
+    assert_eq!(keyboard.update(b"w", at(550)), Some([None; KEYS_COUNT]));
+    assert_eq!(keyboard.update(b"", at(1149)), Some([None; KEYS_COUNT]));
+    assert_eq!(
+        keyboard.update(b"", at(1150)),
+        Some(only(0x5, KeyState::Released))
+    );
+    assert_eq!(keyboard.update(b"", at(2000)), Some([None; KEYS_COUNT]));
+}
+
+#[test]
+fn escape_and_ctrl_c_quit() {
+    let mut keyboard = Keyboard::new(TIMEOUT);
+    let now = Instant::now();
+
+    assert_eq!(keyboard.update(b"\x1b", now), None);
+    assert_eq!(keyboard.update(b"\x03", now), None);
+    // Arrow up and F1 are ignored, including their final letters
+    assert_eq!(
+        keyboard.update(b"\x1b[A\x1bOP", now),
+        Some([None; KEYS_COUNT])
+    );
+}
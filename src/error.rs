@@ -0,0 +1,174 @@
+//! Errors reported by the interpreter
+
+/// Reason a ROM was rejected by [`Chip8::load_cartridge`](crate::Chip8::load_cartridge)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CartridgeError {
+    /// The ROM contains no bytes
+    Empty,
+
+    /// The ROM does not fit between the start address of the platform, see
+    /// [`PlatformSpec::start_addr`](crate::PlatformSpec::start_addr), and the end of RAM
+    TooLarge {
+        /// Size of the ROM, in bytes
+        size: usize,
+
+        /// Largest ROM that can be loaded, in bytes
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "ROM is empty"),
+            Self::TooLarge { size, max } => {
+                write!(
+                    f,
+                    "ROM is {size} bytes long, but at most {max} bytes fit in memory"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {}
+
+/// Reason a font was rejected by [`FontSet::from_bytes`](crate::FontSet::from_bytes) or
+/// [`Chip8::set_font`](crate::Chip8::set_font)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontError {
+    /// The font file is not exactly [`FONT_SIZE`](crate::FONT_SIZE) bytes long
+    InvalidSize {
+        /// Size of the font file, in bytes
+        size: usize,
+    },
+
+    /// The font does not fit in RAM when installed at `addr`
+    OutOfBounds {
+        /// Requested address of the font
+        addr: u16,
+    },
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize { size } => write!(
+                f,
+                "font is {size} bytes long, but should be {} bytes long",
+                crate::FONT_SIZE
+            ),
+            Self::OutOfBounds { addr } => {
+                write!(f, "font does not fit in memory at address 0x{addr:04X}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// Fault raised while executing an instruction with [`Chip8::step`](crate::Chip8::step)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chip8Error {
+    /// The instruction at `pc` does not exist
+    InvalidOpcode {
+        /// Address of the instruction
+        pc: u16,
+
+        /// The instruction
+        op: u16,
+    },
+
+    /// The instruction at `pc` exists but can't be executed by this interpreter
+    UnsupportedOpcode {
+        /// Address of the instruction
+        pc: u16,
+
+        /// The instruction
+        op: u16,
+    },
+
+    /// The subroutine call at `pc` exceeds the maximum call depth
+    StackOverflow {
+        /// Address of the instruction
+        pc: u16,
+    },
+
+    /// The return at `pc` happened outside of any subroutine
+    StackUnderflow {
+        /// Address of the instruction
+        pc: u16,
+    },
+
+    /// The instruction at `pc` accesses memory past the end of RAM
+    MemoryOutOfBounds {
+        /// Address of the instruction
+        pc: u16,
+
+        /// First address that is out of bounds
+        addr: u32,
+    },
+
+    /// The instruction at `pc` refers to a key that is not on the keypad
+    InvalidKey {
+        /// Address of the instruction
+        pc: u16,
+
+        /// The key, as read from a register
+        key: u8,
+    },
+}
+
+impl std::fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidOpcode { pc, op } => {
+                write!(f, "invalid instruction 0x{op:04X} (pc = 0x{pc:04X})")
+            }
+            Self::UnsupportedOpcode { pc, op } => {
+                write!(f, "unsupported instruction 0x{op:04X} (pc = 0x{pc:04X})")
+            }
+            Self::StackOverflow { pc } => write!(f, "stack overflow (pc = 0x{pc:04X})"),
+            Self::StackUnderflow { pc } => write!(f, "stack underflow (pc = 0x{pc:04X})"),
+            Self::MemoryOutOfBounds { pc, addr } => {
+                write!(
+                    f,
+                    "memory access out of bounds at 0x{addr:04X} (pc = 0x{pc:04X})"
+                )
+            }
+            Self::InvalidKey { pc, key } => {
+                write!(f, "invalid key 0x{key:02X} (pc = 0x{pc:04X})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Chip8Error {}
+
+/// Reason a name was rejected when parsing a [`Platform`](crate::Platform) or a preset of
+/// [`Quirks`](crate::Quirks)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// No platform is called `name`, see [`Platform::name`](crate::Platform::name)
+    UnknownPlatform {
+        /// Name that was parsed
+        name: String,
+    },
+
+    /// No preset of quirks is called `name`, see [`Quirks::PRESETS`](crate::Quirks::PRESETS)
+    UnknownQuirks {
+        /// Name that was parsed
+        name: String,
+    },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPlatform { name } => write!(f, "unknown platform `{name}`"),
+            Self::UnknownQuirks { name } => write!(f, "unknown quirks preset `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,177 @@
+//! Hosts of the emulator: a [`Frontend`] shows the display, sounds the beeper and reads the keypad,
+//! while a [`Driver`] emulates the machine frame by frame and keeps the frontend up to date
+//!
+//! ```
+//! use chip8::frontend::{Driver, Frontend};
+//! use chip8::{Chip8, KeyState, KEYS_COUNT};
+//!
+//! /// Frontend that quits after 10 frames
+//! struct Countdown(u32);
+//!
+//! impl Frontend for Countdown {
+//!     fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+//!         self.0 = self.0.checked_sub(1)?;
+//!         Some(Default::default())
+//!     }
+//!
+//!     fn present(&mut self, _chip8: &Chip8) {}
+//!
+//!     fn set_beeper(&mut self, _on: bool) {}
+//! }
+//!
+//! let mut driver = Driver::new(Chip8::default(), Countdown(10));
+//! while driver.frame() {}
+//! ```
+
+use crate::{Chip8, Chip8Error, KeyState, StepOutcome, FRAME_DURATION, KEYS_COUNT};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::thread;
+use std::time::Instant;
+
+/// Keys of the keypad, as laid out on the COSMAC VIP, row by row<br>
+/// Frontends usually map them to the left of a QWERTY keyboard:
+///
+/// ```text
+/// ╔═══╦═══╦═══╦═══╗    ╔═══╦═══╦═══╦═══╗
+/// ║ 1 ║ 2 ║ 3 ║ C ║    ║ 1 ║ 2 ║ 3 ║ 4 ║
+/// ╠═══╬═══╬═══╬═══╣    ╠═══╬═══╬═══╬═══╣
+/// ║ 4 ║ 5 ║ 6 ║ D ║    ║ Q ║ W ║ E ║ R ║
+/// ╠═══╬═══╬═══╬═══╣    ╠═══╬═══╬═══╬═══╣
+/// ║ 7 ║ 8 ║ 9 ║ E ║    ║ A ║ S ║ D ║ F ║
+/// ╠═══╬═══╬═══╬═══╣    ╠═══╬═══╬═══╬═══╣
+/// ║ A ║ 0 ║ B ║ F ║    ║ Z ║ X ║ C ║ V ║
+/// ╚═══╩═══╩═══╩═══╝    ╚═══╩═══╩═══╩═══╝
+/// ```
+pub const KEYPAD_LAYOUT: [[usize; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+
+/// RGB colours of the pixels, indexed by the bitplanes a pixel is lit on: white on black, with
+/// greys for the second plane
+pub const MONO_COLORS: [u32; 4] = [0x000000, 0xFFFFFF, 0xAAAAAA, 0x555555];
+
+/// Parse `value`, given to the command line option `option`, such as a number, a [`Platform`] or
+/// a preset of [`Quirks`]<br>
+/// The error names the option and the value
+///
+/// [`Platform`]: crate::Platform
+/// [`Quirks`]: crate::Quirks
+pub fn parse_option<T>(option: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|e| format!("Invalid `{option} {value}`: {e}"))
+}
+
+/// Display, beeper and keypad of a host, called by a [`Driver`] once per frame
+pub trait Frontend {
+    /// Key events since the previous frame, `None` meaning the user asked to quit<br>
+    /// Called at the start of every frame
+    fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]>;
+
+    /// Show the display of `chip8`, see [`Chip8::screen`]<br>
+    /// Called at the end of every frame, including after the machine halted
+    fn present(&mut self, chip8: &Chip8);
+
+    /// Turn the beeper on or off<br>
+    /// Called at the end of every frame
+    fn set_beeper(&mut self, on: bool);
+
+    /// The machine halted, because the program exited or because of `error`<br>
+    /// Called once, the last frame being shown until the user quits
+    fn halted(&mut self, _chip8: &Chip8, _error: Option<&Chip8Error>) {}
+}
+
+/// Emulates a machine frame by frame, exchanging its keypad, display and beeper with a [`Frontend`]
+#[derive(Debug)]
+pub struct Driver<F> {
+    /// Machine being emulated
+    chip8: Chip8,
+
+    /// Host of the machine
+    frontend: F,
+
+    /// The program exited or faulted, and the machine no longer runs
+    halted: bool,
+}
+
+impl<F: Frontend> Driver<F> {
+    /// Drive `chip8`, loaded with a cartridge, through `frontend`
+    pub fn new(chip8: Chip8, frontend: F) -> Self {
+        Self {
+            chip8,
+            frontend,
+            halted: false,
+        }
+    }
+
+    /// Machine being emulated
+    pub fn chip8(&self) -> &Chip8 {
+        &self.chip8
+    }
+
+    /// Machine being emulated, for instance to reset it
+    pub fn chip8_mut(&mut self) -> &mut Chip8 {
+        &mut self.chip8
+    }
+
+    /// Host of the machine
+    pub fn frontend(&self) -> &F {
+        &self.frontend
+    }
+
+    /// Host of the machine
+    pub fn frontend_mut(&mut self) -> &mut F {
+        &mut self.frontend
+    }
+
+    /// Stop driving, giving back the machine and its host
+    pub fn into_parts(self) -> (Chip8, F) {
+        (self.chip8, self.frontend)
+    }
+
+    /// The program exited or faulted, and the machine no longer runs
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Emulate one frame: forward the key events to the machine, run it with [`Chip8::run_frame`],
+    /// then update the beeper and the display<br>
+    /// Return `false`, without emulating anything, once the user asked to quit
+    pub fn frame(&mut self) -> bool {
+        let Some(keys) = self.frontend.poll_keys() else {
+            return false;
+        };
+        self.chip8.set_keys(keys);
+
+        if !self.halted {
+            let result = self.chip8.run_frame();
+            if let Ok(StepOutcome::Exited) | Err(_) = result {
+                self.halted = true;
+                self.frontend.halted(&self.chip8, result.as_ref().err());
+            }
+        }
+
+        self.frontend
+            .set_beeper(!self.halted && self.chip8.is_sound_playing());
+        self.frontend.present(&self.chip8);
+        true
+    }
+
+    /// Emulate frames at 60 per second until the user quits
+    pub fn run(&mut self) {
+        let mut deadline = Instant::now();
+        while self.frame() {
+            deadline += FRAME_DURATION;
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        }
+        self.frontend.set_beeper(false);
+    }
+}
@@ -0,0 +1,77 @@
+//! Running ROMs without a frontend, and comparing the display against text snapshots
+//!
+//! Snapshots show one row of the display per line, with `#` for lit pixels and `.` for unlit ones.
+
+use crate::{Chip8, Chip8Error, StepOutcome};
+
+/// Character of a lit pixel in a snapshot
+pub const PIXEL_ON: char = '#';
+
+/// Character of an unlit pixel in a snapshot
+pub const PIXEL_OFF: char = '.';
+
+/// Emulate `frames` frames with [`Chip8::run_frame`], without any input<br>
+/// Stop at the first error
+pub fn run_frames(chip8: &mut Chip8, frames: u32) -> Result<StepOutcome, Chip8Error> {
+    let mut outcome = StepOutcome::Executed;
+    for _ in 0..frames {
+        outcome = chip8.run_frame()?;
+    }
+    Ok(outcome)
+}
+
+/// Render the display as a snapshot
+pub fn snapshot(chip8: &Chip8) -> String {
+    let width = chip8.screen_width();
+    let mut text = String::with_capacity((width + 1) * chip8.screen_height());
+    for row in chip8.screen().chunks(width) {
+        text.extend(row.iter().map(|&px| match px {
+            0 => PIXEL_OFF,
+            _ => PIXEL_ON,
+        }));
+        text.push('\n');
+    }
+    text
+}
+
+/// Compare two snapshots<br>
+/// When they differ, return the expected and actual snapshots side by side, followed by a map of the
+/// differences where `+` marks pixels that are only lit in `actual` and `-` pixels that are only lit
+/// in `expected`
+pub fn compare_snapshots(expected: &str, actual: &str) -> Result<(), String> {
+    let expected_rows: Vec<&str> = expected.lines().collect();
+    let actual_rows: Vec<&str> = actual.lines().collect();
+    if expected_rows == actual_rows {
+        return Ok(());
+    }
+
+    let width = expected_rows
+        .iter()
+        .chain(&actual_rows)
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = expected_rows.len().max(actual_rows.len());
+
+    let mut report = format!("{:width$}  {:width$}  diff\n", "expected", "actual");
+    for y in 0..height {
+        let expected_row = expected_rows.get(y).copied().unwrap_or("");
+        let actual_row = actual_rows.get(y).copied().unwrap_or("");
+
+        let mut expected_pixels = expected_row.chars();
+        let mut actual_pixels = actual_row.chars();
+        let diff: String = (0..width)
+            .map(|_| match (expected_pixels.next(), actual_pixels.next()) {
+                (e, a) if e == a => ' ',
+                (Some(PIXEL_ON), _) => '-',
+                (_, Some(PIXEL_ON)) => '+',
+                _ => '?',
+            })
+            .collect();
+
+        let line = format!("{expected_row:width$}  {actual_row:width$}  {diff}");
+        report += line.trim_end();
+        report.push('\n');
+    }
+    Err(report)
+}
@@ -0,0 +1,1331 @@
+//! A CHIP-8 interpreter core.
+//!
+//! This crate contains the interpreter itself and nothing else: it has no
+//! notion of windows, audio devices or keyboards. A frontend loads a ROM with
+//! [`Chip8::load_cartridge`], forwards key events with [`Chip8::set_keys`],
+//! calls [`Chip8::run_frame`] 60 times per second and reads the framebuffer from
+//! [`Chip8::screen`].
+//! The [`frontend`] module does the latter for hosts implementing
+//! [`frontend::Frontend`].
+//!
+//! ```
+//! use chip8::{Chip8, Quirks};
+//!
+//! let mut chip8 = Chip8::new(Quirks::COSMAC_VIP);
+//! chip8.load_cartridge(&[0x00, 0xE0]).unwrap(); // CLS
+//! chip8.step().unwrap();
+//! assert!(chip8.screen().iter().all(|&px| px == 0));
+//! ```
+
+#![warn(missing_docs)]
+
+use std::time::Duration;
+
+pub mod frontend;
+pub mod headless;
+
+mod error;
+mod font;
+mod mega_chip;
+mod platform;
+mod quirks;
+mod random;
+mod screen;
+mod super_chip;
+#[cfg(test)]
+mod tests;
+mod xo_chip;
+
+pub use error::{CartridgeError, Chip8Error, FontError, ParseError};
+pub use font::{FontSet, FONT_SIZE, LARGE_FONT_SIZE, LARGE_FONT_SPRITE_SIZE};
+pub use mega_chip::{
+    DigitisedSound, MEGA_CHIP_RAM_SIZE, MEGA_CHIP_SCREEN_HEIGHT, MEGA_CHIP_SCREEN_WIDTH,
+    PALETTE_SIZE,
+};
+pub use platform::{Platform, PlatformSpec};
+pub use quirks::{IndexIncrement, Quirks, SpriteEdges};
+pub use random::{RandomSource, SeededRandom, VipRandom};
+pub use screen::PLANES_COUNT;
+pub use super_chip::RPL_FLAGS_COUNT;
+pub use xo_chip::{AUDIO_PATTERN_SIZE, DEFAULT_PITCH, XO_CHIP_RAM_SIZE};
+
+use font::LARGE_FONT;
+use mega_chip::MegaChipState;
+use screen::{Screen, FIRST_PLANE};
+
+/// Size of the addressable memory of CHIP-8 machines, in bytes
+pub const RAM_SIZE: usize = 4096;
+
+/// Maximum number of nested subroutine calls on the COSMAC VIP
+pub const STACK_SIZE: usize = 12;
+
+/// Start of the area of the COSMAC VIP RAM holding the stack, see [`Chip8::set_stack_in_ram`]<br>
+/// The stack grows down from `0xECF`, each return address taking 2 bytes, most significant first
+pub const VIP_STACK_ADDR: u16 = 0xEA0;
+
+/// End of the area of the COSMAC VIP RAM holding the stack, exclusive
+const VIP_STACK_END: u16 = 0xED0;
+
+/// Width of the CHIP-8 display, in pixels
+pub const SCREEN_WIDTH: usize = 64;
+
+/// Height of the CHIP-8 display, in pixels
+pub const SCREEN_HEIGHT: usize = 32;
+
+/// Number of pixels in the CHIP-8 display
+pub const SCREEN_BUF_SIZE: usize = SCREEN_WIDTH * SCREEN_HEIGHT;
+
+/// Width of the SUPER-CHIP high resolution display, in pixels
+pub const HIRES_SCREEN_WIDTH: usize = 128;
+
+/// Height of the SUPER-CHIP high resolution display, in pixels
+pub const HIRES_SCREEN_HEIGHT: usize = 64;
+
+/// Length of an instruction, in bytes
+pub const OP_LENGTH: u16 = 2;
+
+/// Number of keys on the hexadecimal keypad
+pub const KEYS_COUNT: usize = 16;
+
+/// Default address of the first font sprite (digit `0`) in RAM<br>
+/// See [`Chip8::set_font`] to install the font elsewhere
+pub const FONT_SPRITES_ADDR: u16 = 0x050;
+
+/// Size of a font sprite, in bytes
+pub const FONT_SPRITE_SIZE: u16 = 5;
+
+/// Address at which most platforms load cartridges and start execution
+pub const CARTRIDGE_START_ADDR: u16 = 0x200;
+
+/// Frequency at which the delay and sound timers are decremented, in Hz
+pub const TIMER_HZ: u32 = 60;
+
+/// Duration of a frame, during which the timers are decremented once
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / TIMER_HZ as u64);
+
+/// Default speed of the CPU, in instructions per second
+pub const DEFAULT_CLOCK_HZ: u32 = 700;
+
+/// State of a CHIP-8 machine: CPU registers, memory, display and keypad
+#[derive(Debug)]
+pub struct Chip8 {
+    /// Program counter
+    pc: u16,
+
+    /// General-purpose registers (V0 -> VF)
+    registers: [u8; 16],
+
+    /// "I" address register, 16 bits wide, or 24 bits with MEGA-CHIP
+    register_i: u32,
+
+    /// Delay timer register
+    register_delay: u8,
+
+    /// Sound timer register
+    register_sound: u8,
+
+    /// Time elapsed since the last decrement of the timers
+    timer_phase: Duration,
+
+    /// Speed of the CPU, in instructions per second
+    clock_hz: u32,
+
+    /// Instructions owed to the CPU by previous frames, in 1/60th of an instruction
+    cycle_budget: u32,
+
+    /// RAM, sized by the platform
+    ram: Vec<u8>,
+
+    /// Return addresses, unless the stack is kept in RAM
+    stack: Vec<u16>,
+
+    /// Stack pointer, number of nested subroutine calls
+    sp: usize,
+
+    /// Maximum number of nested subroutine calls, `None` meaning unlimited
+    stack_size: Option<usize>,
+
+    /// The stack lives in RAM at [`VIP_STACK_ADDR`], like on the COSMAC VIP
+    stack_in_ram: bool,
+
+    /// Screen buffer
+    screen: Screen,
+
+    /// Machine being emulated
+    platform: Platform,
+
+    /// SUPER-CHIP RPL user flags, saved by `FX75` and restored by `FX85`
+    rpl_flags: [u8; RPL_FLAGS_COUNT],
+
+    /// The program executed `00FD`
+    exited: bool,
+
+    /// XO-CHIP audio pattern buffer, played one bit at a time while the sound timer is active
+    audio_pattern: [u8; AUDIO_PATTERN_SIZE],
+
+    /// XO-CHIP pitch register, which sets the playback rate of the audio pattern
+    pitch: u8,
+
+    /// MEGA-CHIP palette, sprite settings and sound
+    mega_chip: MegaChipState,
+
+    /// Behaviours that differ between interpreters
+    quirks: Quirks,
+
+    /// Source of the random numbers drawn by `CXNN`
+    random: Box<dyn RandomSource>,
+
+    /// Font set installed in RAM
+    font: FontSet,
+
+    /// Address of the first font sprite in RAM, followed by the large font
+    font_addr: u16,
+
+    /// Keys states
+    keys: [bool; KEYS_COUNT],
+
+    /// The last instruction was `FX0A` and no key has been pressed yet
+    waiting_for_key: bool,
+
+    /// Register that receives the result from waiting for a key (`FX0A`)
+    waiting_for_key_vx: u8,
+
+    /// The last instruction was `DXYN` and has to wait for the end of the frame
+    waiting_for_vblank: bool,
+}
+
+impl Default for Chip8 {
+    fn default() -> Self {
+        Self::new(Quirks::default())
+    }
+}
+
+impl Chip8 {
+    /// Create a CHIP-8 machine with cleared registers and the default font in memory, ready to load
+    /// a cartridge<br>
+    /// `quirks` selects the interpreter the machine behaves like, see the presets of [`Quirks`]
+    pub fn new(quirks: Quirks) -> Self {
+        let mut chip8 = Self::with_platform(Platform::Chip8);
+        chip8.quirks = quirks;
+        chip8
+    }
+
+    /// Create a machine emulating `platform`, with its memory map, display, instruction set and
+    /// quirks, ready to load a cartridge
+    pub fn with_platform(platform: Platform) -> Self {
+        let mut chip8 = Self::power_on(platform, FontSet::default(), FONT_SPRITES_ADDR);
+        chip8.quirks = platform.spec().quirks;
+        chip8.install_font();
+        chip8
+    }
+
+    /// State of the machine when it is turned on, before the font is installed
+    fn power_on(platform: Platform, font: FontSet, font_addr: u16) -> Self {
+        let spec = platform.spec();
+        Self {
+            pc: spec.start_addr,
+            registers: [0; 16],
+            register_i: 0,
+            register_delay: 0,
+            register_sound: 0,
+            timer_phase: Duration::ZERO,
+            clock_hz: DEFAULT_CLOCK_HZ,
+            cycle_budget: 0,
+            ram: vec![0; spec.ram_size],
+            stack: Vec::with_capacity(spec.stack_size),
+            sp: 0,
+            stack_size: Some(spec.stack_size),
+            stack_in_ram: false,
+            screen: Screen::new(spec.screen_width, spec.screen_height),
+            platform,
+            rpl_flags: [0; RPL_FLAGS_COUNT],
+            exited: false,
+            audio_pattern: [0; AUDIO_PATTERN_SIZE],
+            pitch: DEFAULT_PITCH,
+            mega_chip: MegaChipState::default(),
+            quirks: Quirks::default(),
+            random: Box::new(SeededRandom::from_entropy()),
+            font,
+            font_addr,
+            keys: [false; KEYS_COUNT],
+            waiting_for_key: false,
+            waiting_for_key_vx: 0,
+            waiting_for_vblank: false,
+        }
+    }
+
+    /// Behaviours that differ between interpreters
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Select the behaviours that differ between interpreters<br>
+    /// The quirks are kept across calls to [`Chip8::reset`]
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    /// Machine being emulated
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Instructions understood by the interpreter, as selected by the platform
+    pub fn instruction_set(&self) -> InstructionSet {
+        self.platform.spec().instruction_set
+    }
+
+    /// Pixels of the display, one byte per pixel, row by row<br>
+    /// Bit `N` of a pixel is set when it is lit on plane `N`, so CHIP-8 and SUPER-CHIP pixels are `0`
+    /// (off) or `1` (on), while XO-CHIP pixels range from `0` to `3`<br>
+    /// The size of the display is given by [`Chip8::screen_width`] and [`Chip8::screen_height`]
+    pub fn screen(&self) -> &[u8] {
+        &self.screen.pixels
+    }
+
+    /// Width of the display, in pixels, which changes when SUPER-CHIP programs switch resolution
+    pub fn screen_width(&self) -> usize {
+        self.screen.width
+    }
+
+    /// Height of the display, in pixels, which changes when SUPER-CHIP programs switch resolution
+    pub fn screen_height(&self) -> usize {
+        self.screen.height
+    }
+
+    /// SUPER-CHIP RPL user flags, which a frontend may persist between sessions
+    pub fn rpl_flags(&self) -> &[u8; RPL_FLAGS_COUNT] {
+        &self.rpl_flags
+    }
+
+    /// Restore the SUPER-CHIP RPL user flags, for instance from a previous session<br>
+    /// The flags are kept across calls to [`Chip8::reset`]
+    pub fn set_rpl_flags(&mut self, flags: [u8; RPL_FLAGS_COUNT]) {
+        self.rpl_flags = flags;
+    }
+
+    /// XO-CHIP audio pattern buffer: 128 one-bit samples, most significant bit first, to be looped
+    /// while [`Chip8::is_sound_playing`]
+    pub fn audio_pattern(&self) -> &[u8; AUDIO_PATTERN_SIZE] {
+        &self.audio_pattern
+    }
+
+    /// XO-CHIP pitch register, set by `FX3A`
+    pub fn pitch(&self) -> u8 {
+        self.pitch
+    }
+
+    /// Rate at which the samples of the audio pattern are played, in samples per second<br>
+    /// It is 4000 Hz for the default pitch of 64, and doubles every 48 steps
+    pub fn audio_playback_rate(&self) -> f32 {
+        4000.0 * 2f32.powf((self.pitch as f32 - 64.0) / 48.0)
+    }
+
+    /// Maximum number of nested subroutine calls, `None` meaning unlimited
+    pub fn stack_size(&self) -> Option<usize> {
+        self.stack_size
+    }
+
+    /// Set the maximum number of nested subroutine calls, beyond which `2NNN` fails with
+    /// [`Chip8Error::StackOverflow`], `None` meaning unlimited<br>
+    /// The platform sets it at power on, and it is kept across calls to [`Chip8::reset`]
+    pub fn set_stack_size(&mut self, size: Option<usize>) {
+        self.stack_size = size;
+    }
+
+    /// The stack lives in RAM at [`VIP_STACK_ADDR`], like on the COSMAC VIP
+    pub fn stack_in_ram(&self) -> bool {
+        self.stack_in_ram
+    }
+
+    /// Keep the stack in RAM at [`VIP_STACK_ADDR`] instead of inside the interpreter, for ROMs that
+    /// inspect or modify it<br>
+    /// It then holds 24 return addresses at most, and the setting is kept across calls to
+    /// [`Chip8::reset`]. Switching while subroutines are running loses their return addresses
+    pub fn set_stack_in_ram(&mut self, in_ram: bool) {
+        self.stack_in_ram = in_ram;
+    }
+
+    /// Draw the random numbers of `CXNN` from `source`, for instance a [`SeededRandom`] to make
+    /// runs reproducible<br>
+    /// Machines start with a [`SeededRandom`] from a random seed, and the source is kept across calls
+    /// to [`Chip8::reset`]
+    pub fn set_random_source(&mut self, source: impl RandomSource + 'static) {
+        self.random = Box::new(source);
+    }
+
+    /// Draw the random numbers of `CXNN` from a [`SeededRandom`] starting from `seed`
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.set_random_source(SeededRandom::new(seed));
+    }
+
+    /// Seed of the source of random numbers, which reproduces a run from its start when passed to
+    /// [`Chip8::set_random_seed`], if the source has one<br>
+    /// See [`Chip8::random_draws`] to reproduce it from the current point instead
+    pub fn random_seed(&self) -> Option<u64> {
+        self.random.seed()
+    }
+
+    /// Number of random numbers drawn since the seed, if the source counts them<br>
+    /// Passing it with the seed to [`SeededRandom::resume`] gives a source continuing the current
+    /// sequence, to replay a run from this point
+    pub fn random_draws(&self) -> Option<u64> {
+        self.random.draws()
+    }
+
+    /// Speed of the CPU, in instructions per second
+    pub fn clock_hz(&self) -> u32 {
+        self.clock_hz
+    }
+
+    /// Set the speed of the CPU used by [`Chip8::run_frame`], in instructions per second<br>
+    /// The timers are not affected and always run at [`TIMER_HZ`]
+    pub fn set_clock_hz(&mut self, hz: u32) {
+        self.clock_hz = hz;
+        self.cycle_budget = 0;
+    }
+
+    /// Set the speed of the CPU used by [`Chip8::run_frame`], in instructions per frame<br>
+    /// Speeds above `u32::MAX` instructions per second are capped
+    pub fn set_instructions_per_frame(&mut self, count: u32) {
+        self.set_clock_hz(count.saturating_mul(TIMER_HZ));
+    }
+
+    /// Restore the machine to its power-on state, keeping the platform, selected font, quirks, speed,
+    /// stack settings, source of random numbers and RPL user flags<br>
+    /// The cartridge is erased from memory and has to be loaded again
+    pub fn reset(&mut self) {
+        let power_on = Self::power_on(self.platform, self.font, self.font_addr);
+        let previous = std::mem::replace(self, power_on);
+        self.quirks = previous.quirks;
+        self.random = previous.random;
+        self.clock_hz = previous.clock_hz;
+        self.stack_size = previous.stack_size;
+        self.stack_in_ram = previous.stack_in_ram;
+        self.rpl_flags = previous.rpl_flags;
+        self.install_font();
+    }
+
+    /// Replace the font with `font`, installed in RAM starting at `addr` and followed by the large
+    /// font<br>
+    /// The previous glyphs are erased, so this should be called before loading the cartridge<br>
+    /// The font is kept across calls to [`Chip8::reset`]
+    pub fn set_font(&mut self, font: FontSet, addr: u16) -> Result<(), FontError> {
+        if addr as usize + FONT_SIZE + LARGE_FONT_SIZE > self.ram.len() {
+            return Err(FontError::OutOfBounds { addr });
+        }
+
+        let old_start = self.font_addr as usize;
+        self.ram[old_start..old_start + FONT_SIZE + LARGE_FONT_SIZE].fill(0);
+
+        self.font = font;
+        self.font_addr = addr;
+        self.install_font();
+        Ok(())
+    }
+
+    /// Copy the glyphs of the font, then those of the large font, into RAM
+    fn install_font(&mut self) {
+        let start = self.font_addr as usize;
+        self.ram[start..start + FONT_SIZE].copy_from_slice(self.font.glyphs());
+
+        let start = self.large_font_addr() as usize;
+        self.ram[start..start + LARGE_FONT_SIZE].copy_from_slice(&LARGE_FONT);
+    }
+
+    /// Address of the first large font sprite in RAM, right after the font
+    fn large_font_addr(&self) -> u32 {
+        self.font_addr as u32 + FONT_SIZE as u32
+    }
+
+    /// Copy `rom` into RAM at the start address of the platform<br>
+    /// Fail without touching RAM if `rom` is empty or does not fit in memory
+    pub fn load_cartridge(&mut self, rom: &[u8]) -> Result<(), CartridgeError> {
+        let start = self.platform.spec().start_addr as usize;
+        let max_size = self.ram.len() - start;
+
+        if rom.is_empty() {
+            return Err(CartridgeError::Empty);
+        }
+        if rom.len() > max_size {
+            return Err(CartridgeError::TooLarge {
+                size: rom.len(),
+                max: max_size,
+            });
+        }
+
+        self.ram[start..start + rom.len()].copy_from_slice(rom);
+        Ok(())
+    }
+
+    /// Emulate one frame ([`FRAME_DURATION`]): execute the instructions due at the current clock
+    /// speed, then decrement the timers once<br>
+    /// Return the outcome of the last instruction; the frame ends early if the program exits, if the
+    /// machine starts waiting for a key or if a sprite is drawn with [`Quirks::display_wait`] enabled,
+    /// and the timers are not decremented if an error occurs
+    pub fn run_frame(&mut self) -> Result<StepOutcome, Chip8Error> {
+        // The leftover cycles of the previous frame may not fit with the fastest clock speeds
+        let budget = self.cycle_budget as u64 + self.clock_hz as u64;
+        let count = budget / TIMER_HZ as u64;
+        self.cycle_budget = (budget % TIMER_HZ as u64) as u32;
+
+        let mut outcome = if self.exited {
+            StepOutcome::Exited
+        } else if self.waiting_for_key {
+            StepOutcome::WaitingForKey
+        } else {
+            StepOutcome::Executed
+        };
+        self.waiting_for_vblank = false;
+        for _ in 0..count {
+            outcome = self.step()?;
+            if outcome != StepOutcome::Executed || self.waiting_for_vblank {
+                break;
+            }
+        }
+
+        self.tick_timers(FRAME_DURATION);
+        Ok(outcome)
+    }
+
+    /// Let `dt` elapse for the delay and sound timers, which are decremented at [`TIMER_HZ`]
+    /// regardless of the speed of the CPU
+    pub fn tick_timers(&mut self, dt: Duration) {
+        self.timer_phase += dt;
+        while self.timer_phase >= FRAME_DURATION {
+            self.timer_phase -= FRAME_DURATION;
+            self.register_delay = self.register_delay.saturating_sub(1);
+            self.register_sound = self.register_sound.saturating_sub(1);
+        }
+    }
+
+    /// Whether the sound timer is active, meaning the buzzer should sound
+    pub fn is_sound_playing(&self) -> bool {
+        self.register_sound > 0
+    }
+
+    /// Execute the next instruction, without touching the timers<br>
+    /// On error, `PC` still points at the faulty instruction and the machine is left as it was before
+    /// the instruction, so the caller can report it, halt or inspect the machine
+    pub fn step(&mut self) -> Result<StepOutcome, Chip8Error> {
+        if self.exited {
+            return Ok(StepOutcome::Exited);
+        }
+        if self.waiting_for_key {
+            return Ok(StepOutcome::WaitingForKey);
+        }
+
+        let op_hi = self.read_ram(self.pc as u32)?;
+        let op_lo = self.read_ram(self.pc.wrapping_add(1) as u32)?;
+        let op = ((op_hi as u16) << 8) | op_lo as u16;
+        match (op & 0xF000) >> 12 {
+            0x0 => self.exec_0(op)?,
+            0x1 => self.exec_1(op),
+            0x2 => self.exec_2(op)?,
+            0x3 => self.exec_3(op),
+            0x4 => self.exec_4(op),
+            0x5 => self.exec_5(op)?,
+            0x6 => self.exec_6(op),
+            0x7 => self.exec_7(op),
+            0x8 => self.exec_8(op)?,
+            0x9 => self.exec_9(op)?,
+            0xA => self.exec_a(op),
+            0xB => self.exec_b(op),
+            0xC => self.exec_c(op),
+            0xD => self.exec_d(op)?,
+            0xE => self.exec_e(op)?,
+            0xF => self.exec_f(op)?,
+            _ => unreachable!(),
+        }
+
+        if self.exited {
+            return Ok(StepOutcome::Exited);
+        }
+        Ok(StepOutcome::Executed)
+    }
+
+    /// Read the byte stored at `addr` in RAM
+    fn read_ram(&self, addr: u32) -> Result<u8, Chip8Error> {
+        self.ram
+            .get(addr as usize)
+            .copied()
+            .ok_or(Chip8Error::MemoryOutOfBounds { pc: self.pc, addr })
+    }
+
+    /// Check that the `len` bytes starting at `addr` are all in RAM
+    fn check_ram_range(&self, addr: u32, len: usize) -> Result<(), Chip8Error> {
+        let ram_size = self.ram.len();
+        if addr as usize + len > ram_size {
+            // Report the first byte that is out of bounds
+            let addr = (addr as usize).max(ram_size) as u32;
+            return Err(Chip8Error::MemoryOutOfBounds { pc: self.pc, addr });
+        }
+        Ok(())
+    }
+
+    /// Skip the following instruction, which is 4 bytes long if it is the XO-CHIP `F000 NNNN` or the
+    /// MEGA-CHIP `01NN NNNN`
+    fn skip_next(&mut self) {
+        const XO_CHIP_LONG_OP: [u8; 2] = [0xF0, 0x00];
+        const MEGA_CHIP_LONG_OP: u8 = 0x01;
+
+        let next = self.pc.wrapping_add(OP_LENGTH) as usize;
+        let is_long = match self.ram.get(next..next + 2) {
+            Some(op) if self.instruction_set().has_xo_chip() => op == XO_CHIP_LONG_OP,
+            Some(op) if self.instruction_set().has_mega_chip() => op[0] == MEGA_CHIP_LONG_OP,
+            _ => false,
+        };
+        let length = if is_long { OP_LENGTH * 2 } else { OP_LENGTH };
+        self.pc = self.pc.wrapping_add(OP_LENGTH + length);
+    }
+
+    /// Update the keypad with the key events of the last frame<br>
+    /// `None` leaves the corresponding key unchanged
+    pub fn set_keys(&mut self, keys: [Option<KeyState>; KEYS_COUNT]) {
+        let mut last_key_pressed: Option<u8> = None;
+        for (i, k) in keys.iter().enumerate() {
+            match k {
+                Some(KeyState::Pressed) => {
+                    self.keys[i] = true;
+                    last_key_pressed = Some(i as u8);
+                }
+                Some(KeyState::Released) => self.keys[i] = false,
+                None => {}
+            };
+        }
+
+        if let Some(key) = last_key_pressed {
+            if self.waiting_for_key {
+                self.waiting_for_key = false;
+                self.registers[self.waiting_for_key_vx as usize] = key;
+            }
+        }
+    }
+
+    /// op: `0NNN`
+    /// Execute machine language subroutine at address `NNN`
+    fn exec_0(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const CLEAR_SCREEN: u16 = 0x0E0;
+        const HIRES_CLEAR_SCREEN: u16 = 0x230;
+        const RET_FROM_SUB: u16 = 0x0EE;
+        const SCROLL_DOWN: u16 = 0x0C0;
+        const SCROLL_UP: u16 = 0x0D0;
+        const SCROLL_RIGHT: u16 = 0x0FB;
+        const SCROLL_LEFT: u16 = 0x0FC;
+        const EXIT: u16 = 0x0FD;
+        const LOW_RES: u16 = 0x0FE;
+        const HIGH_RES: u16 = 0x0FF;
+        const MEGA_OFF: u16 = 0x010;
+        const MEGA_ON: u16 = 0x011;
+        const MEGA_SCROLL_UP: u16 = 0x0B0;
+
+        let address = op & 0x0FFF;
+        let mega_chip = self.instruction_set().has_mega_chip();
+        match address {
+            CLEAR_SCREEN => self.clear_screen(),
+            HIRES_CLEAR_SCREEN if self.instruction_set() == InstructionSet::Chip8HiRes => {
+                self.clear_screen()
+            }
+            RET_FROM_SUB => self.ret_from_sub()?,
+            MEGA_OFF if mega_chip => self.mega_off(),
+            MEGA_ON if mega_chip => self.mega_on(),
+            _ if mega_chip && address & 0xFF0 == MEGA_SCROLL_UP => self.scroll_up(op),
+            _ if mega_chip && address & 0xF00 != 0 => self.exec_mega_chip(op)?,
+            _ if self.instruction_set().has_super_chip() => match address {
+                _ if address & 0xFF0 == SCROLL_DOWN => self.scroll_down(op),
+                _ if address & 0xFF0 == SCROLL_UP && self.instruction_set().has_xo_chip() => {
+                    self.scroll_up(op)
+                }
+                SCROLL_RIGHT => self.scroll_right(),
+                SCROLL_LEFT => self.scroll_left(),
+                EXIT => self.exit(),
+                LOW_RES => self.low_res(),
+                HIGH_RES => self.high_res(),
+                _ => return Err(Chip8Error::UnsupportedOpcode { pc: self.pc, op }),
+            },
+            // Machine language subroutines can't be run by an interpreter
+            _ => return Err(Chip8Error::UnsupportedOpcode { pc: self.pc, op }),
+        }
+        Ok(())
+    }
+
+    /// Clear the screen to 0 (only the selected planes with XO-CHIP, and the colours too with
+    /// MEGA-CHIP)
+    fn clear_screen(&mut self) {
+        self.screen.clear();
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// Return from a subroutine
+    fn ret_from_sub(&mut self) -> Result<(), Chip8Error> {
+        if self.sp == 0 {
+            return Err(Chip8Error::StackUnderflow { pc: self.pc });
+        }
+        self.pc = if self.stack_in_ram {
+            // The VIP stores the address of the instruction following the call
+            let slot = self.vip_stack_slot(self.sp - 1);
+            let addr = slot.ok_or(Chip8Error::StackUnderflow { pc: self.pc })? as usize;
+            u16::from_be_bytes([self.ram[addr], self.ram[addr + 1]])
+        } else {
+            self.stack.pop().unwrap_or_default().wrapping_add(OP_LENGTH)
+        };
+        self.sp -= 1;
+        Ok(())
+    }
+
+    /// Address of the `depth`-th return address of the stack kept in RAM, if it fits in the area
+    /// of the COSMAC VIP stack
+    fn vip_stack_slot(&self, depth: usize) -> Option<u16> {
+        let offset = u16::try_from((depth + 1) * 2).ok()?;
+        VIP_STACK_END
+            .checked_sub(offset)
+            .filter(|&addr| addr >= VIP_STACK_ADDR)
+    }
+
+    /// op: 1NNN
+    /// Jump to address NNN
+    fn exec_1(&mut self, op: u16) {
+        let address = op & 0x0FFF;
+        self.pc = address;
+    }
+
+    /// op: 2NNN
+    /// Execute subroutine starting at address NNN
+    fn exec_2(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let address = op & 0x0FFF;
+        if self.stack_size.is_some_and(|size| self.sp >= size) {
+            return Err(Chip8Error::StackOverflow { pc: self.pc });
+        }
+        if self.stack_in_ram {
+            let slot = self.vip_stack_slot(self.sp);
+            let addr = slot.ok_or(Chip8Error::StackOverflow { pc: self.pc })?;
+            let [hi, lo] = self.pc.wrapping_add(OP_LENGTH).to_be_bytes();
+            self.ram[addr as usize] = hi;
+            self.ram[addr as usize + 1] = lo;
+        } else {
+            self.stack.push(self.pc);
+        }
+        self.sp += 1;
+        self.pc = address;
+        Ok(())
+    }
+
+    /// op: 3XNN
+    /// Skip the following instruction if the value of register VX equals NN
+    fn exec_3(&mut self, op: u16) {
+        let [vx, nn] = (op & 0x0FFF).to_be_bytes();
+        if self.registers[vx as usize] == nn {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+    }
+
+    /// op: 4XNN
+    /// Skip the following instruction if the value of register VX is not equal to NN
+    fn exec_4(&mut self, op: u16) {
+        let [vx, nn] = (op & 0x0FFF).to_be_bytes();
+        if self.registers[vx as usize] != nn {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+    }
+
+    /// op: 5XY0
+    /// Skip the following instruction if the value of register VX is equal to the value of register VY
+    /// With XO-CHIP, `5XY2` and `5XY3` transfer a range of registers instead
+    fn exec_5(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const SKIP_IF_EQUAL: u16 = 0x0;
+        const STORE_REGISTER_RANGE: u16 = 0x2;
+        const LOAD_REGISTER_RANGE: u16 = 0x3;
+
+        match op & 0x000F {
+            SKIP_IF_EQUAL => {}
+            STORE_REGISTER_RANGE if self.instruction_set().has_xo_chip() => {
+                return self.store_register_range(op)
+            }
+            LOAD_REGISTER_RANGE if self.instruction_set().has_xo_chip() => {
+                return self.load_register_range(op)
+            }
+            _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+        }
+
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+
+        if self.registers[vx as usize] == self.registers[vy as usize] {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+        Ok(())
+    }
+
+    /// op: 6XNN
+    /// Store number NN in register VX
+    fn exec_6(&mut self, op: u16) {
+        let [vx, nn] = (op & 0x0FFF).to_be_bytes();
+        self.registers[vx as usize] = nn;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: 7XNN
+    /// Add the value NN to register VX
+    fn exec_7(&mut self, op: u16) {
+        let [vx, nn] = (op & 0x0FFF).to_be_bytes();
+        (self.registers[vx as usize], _) = self.registers[vx as usize].overflowing_add(nn);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: 8XYS
+    /// Store/Do math
+    fn exec_8(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const STORE_VY_IN_VX: u16 = 0x0;
+        const VX_OR_VY: u16 = 0x1;
+        const VX_AND_VY: u16 = 0x2;
+        const VX_XOR_VY: u16 = 0x3;
+        const ADD_VY_TO_VX: u16 = 0x4;
+        const SUB_VY_FROM_VX: u16 = 0x5;
+        const RSH_VY_TO_VX: u16 = 0x6;
+        const VY_MINUS_VX: u16 = 0x7;
+        const LSH_VY_TO_VX: u16 = 0xE;
+
+        let suffix = op & 0x000F;
+        match suffix {
+            STORE_VY_IN_VX => self.store_vy_in_vx(op),
+            VX_OR_VY => self.vx_or_vy(op),
+            VX_AND_VY => self.vx_and_vy(op),
+            VX_XOR_VY => self.vx_xor_vy(op),
+            ADD_VY_TO_VX => self.add_vy_to_vx(op),
+            SUB_VY_FROM_VX => self.sub_vy_from_vx(op),
+            RSH_VY_TO_VX => self.rsh_vy_to_vx(op),
+            VY_MINUS_VX => self.vy_minus_vx(op),
+            LSH_VY_TO_VX => self.lsh_vy_to_vx(op),
+            _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+        }
+        Ok(())
+    }
+
+    /// op: `8XY0`
+    /// Store the value of register `VY` in register `VX`
+    fn store_vy_in_vx(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+        self.registers[vx as usize] = self.registers[vy as usize];
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY1`
+    /// Set `VX` to `VX` OR `VY`<br>
+    /// Set `VF` to `00` if [`Quirks::vf_reset`] is enabled
+    fn vx_or_vy(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+        self.registers[vx as usize] |= self.registers[vy as usize];
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY2`
+    /// Set `VX` to `VX` AND `VY`<br>
+    /// Set `VF` to `00` if [`Quirks::vf_reset`] is enabled
+    fn vx_and_vy(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+        self.registers[vx as usize] &= self.registers[vy as usize];
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY3`
+    /// Set `VX` to `VX` XOR `VY`<br>
+    /// Set `VF` to `00` if [`Quirks::vf_reset`] is enabled
+    fn vx_xor_vy(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+        self.registers[vx as usize] ^= self.registers[vy as usize];
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY4`
+    /// Add the value of register `VY` to register `VX`<br>
+    /// Set `VF` to `01` if a carry occurs<br>
+    /// Set `VF` to `00` if a carry does not occur
+    fn add_vy_to_vx(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let (res, carry) = self.registers[vx].overflowing_add(self.registers[vy]);
+        self.registers[vx] = res;
+        self.registers[0xF] = carry as u8;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY5`
+    /// Subtract the value of register `VY` from register `VX`<br>
+    /// Set `VF` to `00` if a borrow occurs<br>
+    /// Set `VF` to `01` if a borrow does not occur
+    fn sub_vy_from_vx(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let (res, borrow) = self.registers[vx].overflowing_sub(self.registers[vy]);
+        self.registers[vx] = res;
+        self.registers[0xF] = !borrow as u8;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY6`
+    /// Store the value of register `VY` shifted right one bit in register `VX`<br>
+    /// Set register `VF` to the least significant bit prior to the shift<br>
+    /// `VY` is unchanged<br>
+    /// `VX` is shifted instead of `VY` if [`Quirks::shift_vx`] is enabled
+    fn rsh_vy_to_vx(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let value = self.registers[if self.quirks.shift_vx { vx } else { vy }];
+        self.registers[vx] = value >> 1;
+        self.registers[0xF] = value & 0x01;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XY7`
+    /// Set register `VX` to the value of `VY` minus `VX`<br>
+    /// Set `VF` to `00` if a borrow occurs<br>
+    /// Set `VF` to `01` if a borrow does not occur
+    fn vy_minus_vx(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let (res, borrow) = self.registers[vy].overflowing_sub(self.registers[vx]);
+        self.registers[vx] = res;
+        self.registers[0xF] = !borrow as u8;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `8XYE`
+    /// Store the value of register `VY` shifted left one bit in register `VX`<br>
+    /// Set register `VF` to the most significant bit prior to the shift<br>
+    /// `VY` is unchanged<br>
+    /// `VX` is shifted instead of `VY` if [`Quirks::shift_vx`] is enabled
+    fn lsh_vy_to_vx(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let value = self.registers[if self.quirks.shift_vx { vx } else { vy }];
+        self.registers[vx] = value << 1;
+        self.registers[0xF] = (value & 0x80) >> 7;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `9XY0`
+    /// Skip the following instruction if the value of register VX is not equal to the value of register VY
+    fn exec_9(&mut self, op: u16) -> Result<(), Chip8Error> {
+        if op & 0x000F != 0 {
+            return Err(Chip8Error::InvalidOpcode { pc: self.pc, op });
+        }
+
+        let vx = (op & 0x0F00) >> 8;
+        let vy = (op & 0x00F0) >> 4;
+
+        if self.registers[vx as usize] != self.registers[vy as usize] {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+        Ok(())
+    }
+
+    /// op: `ANNN`
+    /// Store memory address NNN in register I
+    fn exec_a(&mut self, op: u16) {
+        let address = op & 0x0FFF;
+        self.register_i = address as u32;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `BNNN`
+    /// Jump to address NNN + V0<br>
+    /// If [`Quirks::jump_vx`] is enabled, the instruction is `BXNN` and jumps to address XNN + VX
+    fn exec_b(&mut self, op: u16) {
+        let address = op & 0x0FFF;
+        let offset = if self.quirks.jump_vx {
+            self.registers[((op & 0x0F00) >> 8) as usize]
+        } else {
+            self.registers[0x0]
+        };
+        self.pc = address + offset as u16;
+    }
+
+    /// op: `CXNN`
+    /// Set VX to a random number with a mask of NN
+    fn exec_c(&mut self, op: u16) {
+        let [vx, nn] = (op & 0x0FFF).to_be_bytes();
+        self.registers[vx as usize] = self.random.next_byte() & nn;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `DXYN`
+    /// Draw a sprite at position `VX`, `VY` with `N` bytes of sprite data starting at the address stored in `I`<br>
+    /// With SUPER-CHIP, `DXY0` draws a 16x16 sprite made of 32 bytes, two per row<br>
+    /// With XO-CHIP, a sprite is drawn on each selected plane, the sprite of the second plane
+    /// following the sprite of the first in memory<br>
+    /// With MEGA-CHIP mode on, a colour sprite is drawn instead<br>
+    /// Set `VF` to `01` if any set pixels are changed to unset, and `00` otherwise
+    fn exec_d(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const SPRITE_WIDTH: usize = 8;
+        const LARGE_SPRITE_SIZE: usize = 16;
+
+        if self.screen.has_colors() {
+            return self.draw_color_sprite(op);
+        }
+
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let n = (op & 0x000F) as usize;
+        let (width, height) = match n {
+            0 if self.instruction_set().has_super_chip() => (LARGE_SPRITE_SIZE, LARGE_SPRITE_SIZE),
+            n => (SPRITE_WIDTH, n),
+        };
+        let bytes_per_row = width / 8;
+        let sprite_size = height * bytes_per_row;
+        // Each selected plane gets its own sprite, stored one after the other
+        let planes: Vec<u8> = (0..PLANES_COUNT)
+            .map(|plane| FIRST_PLANE << plane)
+            .filter(|&plane| self.screen.planes & plane != 0)
+            .collect();
+        self.check_ram_range(self.register_i, sprite_size * planes.len())?;
+
+        let (screen_width, screen_height) = (self.screen.width, self.screen.height);
+        // The starting position always wraps around, only the rest of the sprite may be clipped
+        let x0 = self.registers[vx] as usize % screen_width;
+        let y0 = self.registers[vy] as usize % screen_height;
+
+        let mut collision = false;
+        for (index, &plane) in planes.iter().enumerate() {
+            let sprite_addr = self.register_i as usize + index * sprite_size;
+            for row in 0..height {
+                let y = match (y0 + row, self.quirks.sprite_edges) {
+                    (y, _) if y < screen_height => y,
+                    (_, SpriteEdges::Clip) => break,
+                    (y, SpriteEdges::Wrap) => y % screen_height,
+                };
+                let start = sprite_addr + row * bytes_per_row;
+                let sprite_row = self.ram[start..start + bytes_per_row]
+                    .iter()
+                    .fold(0u16, |bits, &byte| bits << 8 | byte as u16);
+
+                for col in 0..width {
+                    let x = match (x0 + col, self.quirks.sprite_edges) {
+                        (x, _) if x < screen_width => x,
+                        (_, SpriteEdges::Clip) => break,
+                        (x, SpriteEdges::Wrap) => x % screen_width,
+                    };
+                    if sprite_row & (1 << (width - 1 - col)) == 0 {
+                        continue;
+                    }
+
+                    collision |= self.screen.toggle(x, y, plane);
+                }
+            }
+        }
+        self.registers[0xF] = collision as u8;
+        self.waiting_for_vblank = self.quirks.display_wait;
+
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `EXSS`
+    /// Skip instruction depending on key
+    fn exec_e(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let suffix = op & 0x00FF;
+        match suffix {
+            0x9E => self.skip_if_key_pressed(op)?,
+            0xA1 => self.skip_if_key_not_pressed(op)?,
+            _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+        }
+        Ok(())
+    }
+
+    /// op: `EX9E`
+    /// Skip the following instruction if the key corresponding to the hex value currently stored in register `VX` is pressed
+    fn skip_if_key_pressed(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let vx = (op & 0x0F00) >> 8;
+        let key = self.registers[vx as usize];
+        let pressed = *self
+            .keys
+            .get(key as usize)
+            .ok_or(Chip8Error::InvalidKey { pc: self.pc, key })?;
+        if pressed {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+        Ok(())
+    }
+
+    /// op: `EXA1`
+    /// Skip the following instruction if the key corresponding to the hex value currently stored in register `VX` is not pressed
+    fn skip_if_key_not_pressed(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let vx = (op & 0x0F00) >> 8;
+        let key = self.registers[vx as usize];
+        let pressed = *self
+            .keys
+            .get(key as usize)
+            .ok_or(Chip8Error::InvalidKey { pc: self.pc, key })?;
+        if !pressed {
+            self.skip_next();
+        } else {
+            self.pc = self.pc.wrapping_add(OP_LENGTH);
+        }
+        Ok(())
+    }
+
+    /// op: `FXSS`
+    /// Misc
+    fn exec_f(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const STORE_DELAY: u8 = 0x07;
+        const WAIT_FOR_KEY: u8 = 0x0A;
+        const SET_DELAY: u8 = 0x15;
+        const SET_SOUND: u8 = 0x18;
+        const ADD_VX_TO_I: u8 = 0x1E;
+        const SET_I_TO_FONT: u8 = 0x29;
+        const STORE_BCD: u8 = 0x33;
+        const STORE_REGISTERS: u8 = 0x55;
+        const RESTORE_REGISTERS: u8 = 0x65;
+        const SET_I_TO_LARGE_FONT: u8 = 0x30;
+        const STORE_RPL_FLAGS: u8 = 0x75;
+        const LOAD_RPL_FLAGS: u8 = 0x85;
+        const SET_I_LONG: u8 = 0x00;
+        const SELECT_PLANES: u8 = 0x01;
+        const LOAD_AUDIO_PATTERN: u8 = 0x02;
+        const SET_PITCH: u8 = 0x3A;
+
+        let [prefix, suffix] = op.to_be_bytes();
+        match suffix {
+            STORE_DELAY => self.store_delay(op),
+            WAIT_FOR_KEY => self.wait_for_key(op),
+            SET_DELAY => self.set_delay(op),
+            SET_SOUND => self.set_sound(op),
+            ADD_VX_TO_I => self.add_vx_to_i(op),
+            SET_I_TO_FONT => self.set_i_to_font(op),
+            STORE_BCD => self.store_bcd(op),
+            STORE_REGISTERS => self.store_registers(op),
+            RESTORE_REGISTERS => self.restore_registers(op),
+            _ if self.instruction_set().has_xo_chip() => match suffix {
+                SET_I_TO_LARGE_FONT => self.set_i_to_large_font(op),
+                STORE_RPL_FLAGS => self.store_rpl_flags(op)?,
+                LOAD_RPL_FLAGS => self.load_rpl_flags(op)?,
+                SET_I_LONG if prefix == 0xF0 => self.set_i_long()?,
+                SELECT_PLANES => self.select_planes(op)?,
+                LOAD_AUDIO_PATTERN if prefix == 0xF0 => self.load_audio_pattern()?,
+                SET_PITCH => self.set_pitch(op),
+                _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+            },
+            _ if self.instruction_set().has_super_chip() => match suffix {
+                SET_I_TO_LARGE_FONT => self.set_i_to_large_font(op),
+                STORE_RPL_FLAGS => self.store_rpl_flags(op)?,
+                LOAD_RPL_FLAGS => self.load_rpl_flags(op)?,
+                _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+            },
+            _ => return Err(Chip8Error::InvalidOpcode { pc: self.pc, op }),
+        }
+        Ok(())
+    }
+
+    /// op: `FX07`
+    /// Store the current value of the delay timer in register `VX`
+    fn store_delay(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        self.registers[vx as usize] = self.register_delay;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX0A`
+    /// Wait for a keypress and store the result in register `VX`
+    fn wait_for_key(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        self.waiting_for_key = true;
+        self.waiting_for_key_vx = vx as u8;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX15`
+    /// Set the delay timer to the value of register `VX`
+    fn set_delay(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        self.register_delay = self.registers[vx as usize];
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX15`
+    /// Set the sound timer to the value of register `VX`
+    fn set_sound(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        self.register_sound = self.registers[vx as usize];
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX1E`
+    /// Add the value stored in register `VX` to register `I`
+    fn add_vx_to_i(&mut self, op: u16) {
+        let vx = (op & 0x0F00) >> 8;
+        self.add_to_i(self.registers[vx as usize] as u32);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX29`
+    /// Set `I` to the memory address of the sprite data corresponding to the hexadecimal digit stored in register `VX`
+    fn set_i_to_font(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let digit = (self.registers[vx] & 0x0F) as u32;
+        self.register_i = self.font_addr as u32 + digit * FONT_SPRITE_SIZE as u32;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX33`
+    /// Store the binary-coded decimal equivalent of the value stored in register VX at addresses `I`, `I + 1`, and `I + 2`
+    fn store_bcd(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let value = self.registers[vx];
+        let digits = [value / 100, value / 10 % 10, value % 10];
+        for (offset, digit) in digits.into_iter().enumerate() {
+            let ram_pos = self.wrap_address(self.register_i, offset);
+            self.ram[ram_pos] = digit;
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX55`
+    /// Store the values of registers `V0` to `VX` inclusive in memory starting at address `I`<br>
+    /// `I` is then incremented according to [`Quirks::index_increment`]
+    fn store_registers(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        for vi in 0..=vx {
+            let ram_pos = self.wrap_address(self.register_i, vi);
+            self.ram[ram_pos] = self.registers[vi];
+        }
+        self.increment_i_after_transfer(vx);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX65`
+    /// Fill registers `V0` to `VX` inclusive with the values stored in memory starting at address `I`<br>
+    /// `I` is then incremented according to [`Quirks::index_increment`]
+    fn restore_registers(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        for vi in 0..=vx {
+            let ram_pos = self.wrap_address(self.register_i, vi);
+            self.registers[vi] = self.ram[ram_pos];
+        }
+        self.increment_i_after_transfer(vx);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// Index in RAM of the byte `offset` bytes after `addr`, wrapping around at the end of RAM
+    fn wrap_address(&self, addr: u32, offset: usize) -> usize {
+        (addr as usize + offset) % self.ram.len()
+    }
+
+    /// Update `I` after `FX55` or `FX65` transferred registers `V0` to `VX`
+    fn increment_i_after_transfer(&mut self, x: usize) {
+        let increment = match self.quirks.index_increment {
+            IndexIncrement::XPlusOne => x as u32 + 1,
+            IndexIncrement::X => x as u32,
+            IndexIncrement::Unchanged => 0,
+        };
+        self.add_to_i(increment);
+    }
+
+    /// Add `value` to `I`, wrapping around at the width of the register
+    fn add_to_i(&mut self, value: u32) {
+        self.register_i =
+            self.register_i.wrapping_add(value) & self.instruction_set().address_mask();
+    }
+}
+
+/// Change of state of a key during a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyState {
+    /// The key has been pressed during the last frame
+    Pressed,
+
+    /// The key has been released during the last frame
+    Released,
+}
+
+/// Result of a successful call to [`Chip8::step`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// An instruction was executed
+    Executed,
+
+    /// No instruction was executed because `FX0A` is waiting for a key press
+    WaitingForKey,
+
+    /// The program has exited with `00FD` and will not execute any other instruction
+    Exited,
+}
+
+/// Set of instructions understood by the interpreter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstructionSet {
+    /// The original CHIP-8 instructions
+    #[default]
+    Chip8,
+
+    /// CHIP-8 and the `0230` instruction of the two-page hi-res interpreter, which clears its 64x64
+    /// display
+    Chip8HiRes,
+
+    /// CHIP-8 and the SUPER-CHIP 1.1 additions: high resolution display, scrolling, 16x16 sprites,
+    /// large font and RPL user flags
+    SuperChip,
+
+    /// SUPER-CHIP and the XO-CHIP additions: 64 KiB of RAM, long `I` loads, register ranges, two
+    /// bitplanes, scrolling up and the audio pattern buffer
+    XoChip,
+
+    /// SUPER-CHIP and the MEGA-CHIP additions: 24-bit `I`, 256x192 colour display with palettes,
+    /// sprite sizes and blend modes, and digitised sound
+    MegaChip,
+}
+
+impl InstructionSet {
+    /// Whether the SUPER-CHIP instructions are available
+    pub const fn has_super_chip(self) -> bool {
+        matches!(self, Self::SuperChip | Self::XoChip | Self::MegaChip)
+    }
+
+    /// Whether the XO-CHIP instructions are available
+    pub const fn has_xo_chip(self) -> bool {
+        matches!(self, Self::XoChip)
+    }
+
+    /// Whether the MEGA-CHIP instructions are available
+    pub const fn has_mega_chip(self) -> bool {
+        matches!(self, Self::MegaChip)
+    }
+
+    /// Mask of the bits held by register `I`
+    pub(crate) const fn address_mask(self) -> u32 {
+        match self {
+            Self::MegaChip => 0xFF_FFFF,
+            _ => 0xFFFF,
+        }
+    }
+
+    /// Number of RPL user flags available to `FX75` and `FX85`
+    pub(crate) const fn rpl_flags_count(self) -> usize {
+        match self {
+            Self::Chip8 | Self::Chip8HiRes => 0,
+            Self::SuperChip | Self::MegaChip => 8,
+            Self::XoChip => RPL_FLAGS_COUNT,
+        }
+    }
+}
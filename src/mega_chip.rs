@@ -0,0 +1,328 @@
+//! Instructions added by MEGA-CHIP
+
+use crate::screen::Screen;
+use crate::{Chip8, Chip8Error, OP_LENGTH};
+
+/// Size of the RAM of MEGA-CHIP machines, in bytes (16 MiB, addressed by a 24-bit `I`)
+pub const MEGA_CHIP_RAM_SIZE: usize = 0x100_0000;
+
+/// Width of the MEGA-CHIP display, in pixels
+pub const MEGA_CHIP_SCREEN_WIDTH: usize = 256;
+
+/// Height of the MEGA-CHIP display, in pixels
+pub const MEGA_CHIP_SCREEN_HEIGHT: usize = 192;
+
+/// Number of colours in the MEGA-CHIP palette, colour `0` being transparent
+pub const PALETTE_SIZE: usize = 256;
+
+/// Size of the header of a digitised sound: sample rate (2 bytes), length (3 bytes) and a reserved
+/// byte
+const SOUND_HEADER_SIZE: usize = 6;
+
+/// How the colour of a sprite pixel is combined with the colour already on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum BlendMode {
+    /// The sprite colour replaces the screen colour
+    #[default]
+    Normal,
+
+    /// The sprite colour is mixed with the screen colour, with an opacity of 25%, 50% or 75%
+    Opacity(u32),
+
+    /// The colours are added
+    Add,
+
+    /// The colours are multiplied
+    Multiply,
+}
+
+impl BlendMode {
+    /// Blend mode selected by `080N`
+    fn from_code(n: u16) -> Option<Self> {
+        match n {
+            0 => Some(Self::Normal),
+            1..=3 => Some(Self::Opacity(n as u32 * 25)),
+            4 => Some(Self::Add),
+            5 => Some(Self::Multiply),
+            _ => None,
+        }
+    }
+
+    /// Combine the sprite colour `src` with the screen colour `dst`, both ARGB, into an opaque
+    /// colour
+    fn blend(self, src: u32, dst: u32) -> u32 {
+        let channel = |shift: u32| {
+            let (s, d) = ((src >> shift) & 0xFF, (dst >> shift) & 0xFF);
+            let value = match self {
+                Self::Normal => s,
+                Self::Opacity(percent) => (s * percent + d * (100 - percent)) / 100,
+                Self::Add => (s + d).min(0xFF),
+                Self::Multiply => s * d / 0xFF,
+            };
+            value << shift
+        };
+        0xFF00_0000 | channel(16) | channel(8) | channel(0)
+    }
+}
+
+/// State of the MEGA-CHIP extensions
+#[derive(Debug, Clone)]
+pub(crate) struct MegaChipState {
+    /// ARGB colours, selected by palette index
+    palette: [u32; PALETTE_SIZE],
+
+    /// Width of the colour sprites, in pixels
+    sprite_width: usize,
+
+    /// Height of the colour sprites, in pixels
+    sprite_height: usize,
+
+    /// How sprites are combined with the screen
+    blend_mode: BlendMode,
+
+    /// Palette index whose pixels collide with sprites drawn over them, `0` meaning none
+    collision_color: u8,
+
+    /// Digitised sound being played
+    sound: Option<SoundState>,
+}
+
+impl Default for MegaChipState {
+    fn default() -> Self {
+        Self {
+            palette: [0; PALETTE_SIZE],
+            sprite_width: 0,
+            sprite_height: 0,
+            blend_mode: BlendMode::default(),
+            collision_color: 0,
+            sound: None,
+        }
+    }
+}
+
+/// Digitised sound started by `060N`
+#[derive(Debug, Clone, Copy)]
+struct SoundState {
+    /// Address of the first sample
+    start: usize,
+
+    /// Number of samples
+    len: usize,
+
+    /// Samples per second
+    sample_rate: u16,
+
+    /// The sound restarts when it ends
+    looping: bool,
+}
+
+/// Digitised sound played by a MEGA-CHIP program, see [`Chip8::digitised_sound`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitisedSound<'a> {
+    /// Unsigned 8-bit samples
+    pub samples: &'a [u8],
+
+    /// Samples per second
+    pub sample_rate: u16,
+
+    /// The sound restarts when it ends, instead of playing once
+    pub looping: bool,
+}
+
+impl Chip8 {
+    /// ARGB colours of the pixels, row by row, while MEGA-CHIP mode is on<br>
+    /// [`Chip8::screen`] then holds the palette index of each pixel
+    pub fn screen_colors(&self) -> Option<&[u32]> {
+        self.screen.has_colors().then_some(&self.screen.colors[..])
+    }
+
+    /// Digitised sound the MEGA-CHIP program is playing, if any
+    pub fn digitised_sound(&self) -> Option<DigitisedSound<'_>> {
+        self.mega_chip.sound.map(|sound| DigitisedSound {
+            samples: &self.ram[sound.start..sound.start + sound.len],
+            sample_rate: sound.sample_rate,
+            looping: sound.looping,
+        })
+    }
+
+    /// op: `0010`
+    /// Turn MEGA-CHIP mode off, switching back to the low resolution display
+    pub(crate) fn mega_off(&mut self) {
+        let spec = self.platform.spec();
+        self.screen = Screen::new(spec.screen_width, spec.screen_height);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `0011`
+    /// Turn MEGA-CHIP mode on, switching to the 256x192 colour display
+    pub(crate) fn mega_on(&mut self) {
+        self.screen = Screen::with_colors(MEGA_CHIP_SCREEN_WIDTH, MEGA_CHIP_SCREEN_HEIGHT);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// Execute the MEGA-CHIP instructions from `01NN` to `09NN`
+    pub(crate) fn exec_mega_chip(&mut self, op: u16) -> Result<(), Chip8Error> {
+        const SET_I_LONG: u16 = 0x1;
+        const LOAD_PALETTE: u16 = 0x2;
+        const SET_SPRITE_WIDTH: u16 = 0x3;
+        const SET_SPRITE_HEIGHT: u16 = 0x4;
+        const PLAY_SOUND: u16 = 0x6;
+        const STOP_SOUND: u16 = 0x7;
+        const SET_BLEND_MODE: u16 = 0x8;
+        const SET_COLLISION_COLOR: u16 = 0x9;
+
+        let nn = op & 0x00FF;
+        match (op & 0x0F00) >> 8 {
+            SET_I_LONG => self.set_i_24_bits(op)?,
+            LOAD_PALETTE => self.load_palette(op)?,
+            SET_SPRITE_WIDTH => self.set_sprite_width(op),
+            SET_SPRITE_HEIGHT => self.set_sprite_height(op),
+            PLAY_SOUND if nn <= 1 => self.play_sound(op)?,
+            STOP_SOUND if nn == 0 => self.stop_sound(),
+            SET_BLEND_MODE => self.set_blend_mode(op)?,
+            SET_COLLISION_COLOR => self.set_collision_color(op),
+            _ => return Err(Chip8Error::UnsupportedOpcode { pc: self.pc, op }),
+        }
+        Ok(())
+    }
+
+    /// op: `01NN NNNN`
+    /// Store the 24-bit address `NNNNNN`, whose low 16 bits are held by the following two bytes, in
+    /// register `I`
+    fn set_i_24_bits(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let hi = self.read_ram(self.pc.wrapping_add(OP_LENGTH) as u32)?;
+        let lo = self.read_ram(self.pc.wrapping_add(OP_LENGTH + 1) as u32)?;
+        self.register_i = u32::from_be_bytes([0, op as u8, hi, lo]);
+        self.pc = self.pc.wrapping_add(OP_LENGTH * 2);
+        Ok(())
+    }
+
+    /// op: `02NN`
+    /// Load `NN` ARGB colours, 4 bytes each, from memory starting at address `I` into the palette,
+    /// starting at index 1
+    fn load_palette(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let count = (op & 0x00FF) as usize;
+        self.check_ram_range(self.register_i, count * 4)?;
+        let start = self.register_i as usize;
+        for (index, color) in self.ram[start..start + count * 4].chunks(4).enumerate() {
+            self.mega_chip.palette[index + 1] = u32::from_be_bytes(color.try_into().unwrap());
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `03NN`
+    /// Set the width of colour sprites to `NN` pixels, `00` meaning 256
+    fn set_sprite_width(&mut self, op: u16) {
+        self.mega_chip.sprite_width = sprite_dimension(op);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `04NN`
+    /// Set the height of colour sprites to `NN` pixels, `00` meaning 256
+    fn set_sprite_height(&mut self, op: u16) {
+        self.mega_chip.sprite_height = sprite_dimension(op);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `060N`
+    /// Play the digitised sound stored at address `I`, in a loop if `N` is `0` and once if it is `1`
+    fn play_sound(&mut self, op: u16) -> Result<(), Chip8Error> {
+        self.check_ram_range(self.register_i, SOUND_HEADER_SIZE)?;
+        let header = self.register_i as usize;
+        let sample_rate = u16::from_be_bytes([self.ram[header], self.ram[header + 1]]);
+        let len = u32::from_be_bytes([
+            0,
+            self.ram[header + 2],
+            self.ram[header + 3],
+            self.ram[header + 4],
+        ]);
+
+        let start = header + SOUND_HEADER_SIZE;
+        self.check_ram_range(start as u32, len as usize)?;
+        self.mega_chip.sound = Some(SoundState {
+            start,
+            len: len as usize,
+            sample_rate,
+            looping: op & 0x000F == 0,
+        });
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `0700`
+    /// Stop the digitised sound
+    fn stop_sound(&mut self) {
+        self.mega_chip.sound = None;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `080N`
+    /// Select how sprites are combined with the screen: normal (`0`), 25%, 50% or 75% opacity (`1` to
+    /// `3`), add (`4`) or multiply (`5`)
+    fn set_blend_mode(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let mode = BlendMode::from_code(op & 0x00FF)
+            .ok_or(Chip8Error::InvalidOpcode { pc: self.pc, op })?;
+        self.mega_chip.blend_mode = mode;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `09NN`
+    /// Set the palette index of the pixels that collide with sprites to `NN`
+    fn set_collision_color(&mut self, op: u16) {
+        self.mega_chip.collision_color = op as u8;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `DXYN` in MEGA-CHIP mode
+    /// Draw a colour sprite at position `VX`, `VY`, with the size set by `03NN` and `04NN` and one
+    /// palette index per pixel stored row by row starting at the address stored in `I`<br>
+    /// Pixels of index `0` are transparent, the others are blended with the screen and clip at its
+    /// edges<br>
+    /// Set `VF` to `01` if any drawn pixel covers a pixel of the collision colour, and `00` otherwise
+    pub(crate) fn draw_color_sprite(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let (width, height) = (self.mega_chip.sprite_width, self.mega_chip.sprite_height);
+        self.check_ram_range(self.register_i, width * height)?;
+
+        let (screen_width, screen_height) = (self.screen.width, self.screen.height);
+        let x0 = self.registers[vx] as usize % screen_width;
+        let y0 = self.registers[vy] as usize % screen_height;
+
+        let mut collision = false;
+        for row in 0..height.min(screen_height - y0) {
+            let sprite_row = self.register_i as usize + row * width;
+            for col in 0..width.min(screen_width - x0) {
+                let index = self.ram[sprite_row + col];
+                if index == 0 {
+                    continue;
+                }
+
+                let pixel = x0 + col + (y0 + row) * screen_width;
+                let covered = self.screen.pixels[pixel];
+                collision |= covered != 0 && covered == self.mega_chip.collision_color;
+                self.screen.pixels[pixel] = index;
+                let color = self.mega_chip.palette[index as usize];
+                self.screen.colors[pixel] = self
+                    .mega_chip
+                    .blend_mode
+                    .blend(color, self.screen.colors[pixel]);
+            }
+        }
+        self.registers[0xF] = collision as u8;
+        self.waiting_for_vblank = self.quirks.display_wait;
+
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+}
+
+/// Sprite width or height set by `03NN` or `04NN`, `00` meaning 256
+fn sprite_dimension(op: u16) -> usize {
+    match op & 0x00FF {
+        0 => 256,
+        nn => nn as usize,
+    }
+}
@@ -0,0 +1,177 @@
+//! Machines and interpreters that ran CHIP-8 programs, and how they differ
+
+use crate::{
+    InstructionSet, ParseError, Quirks, CARTRIDGE_START_ADDR, MEGA_CHIP_RAM_SIZE, RAM_SIZE,
+    SCREEN_HEIGHT, SCREEN_WIDTH, STACK_SIZE, XO_CHIP_RAM_SIZE,
+};
+
+/// Description of a platform: memory map, display, instruction set and quirks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformSpec {
+    /// Human-readable name
+    pub name: &'static str,
+
+    /// Size of the RAM, in bytes
+    pub ram_size: usize,
+
+    /// Number of return addresses the stack holds
+    pub stack_size: usize,
+
+    /// Width of the display at power on, in pixels
+    pub screen_width: usize,
+
+    /// Height of the display at power on, in pixels
+    pub screen_height: usize,
+
+    /// Address at which cartridges are loaded and execution starts
+    pub start_addr: u16,
+
+    /// Instructions understood by the interpreter
+    pub instruction_set: InstructionSet,
+
+    /// Behaviours of the interpreter
+    pub quirks: Quirks,
+}
+
+impl PlatformSpec {
+    /// CHIP-8 on the COSMAC VIP
+    pub const CHIP_8: Self = Self {
+        name: "CHIP-8",
+        ram_size: RAM_SIZE,
+        stack_size: STACK_SIZE,
+        screen_width: SCREEN_WIDTH,
+        screen_height: SCREEN_HEIGHT,
+        start_addr: CARTRIDGE_START_ADDR,
+        instruction_set: InstructionSet::Chip8,
+        quirks: Quirks::COSMAC_VIP,
+    };
+
+    /// Two-page hi-res CHIP-8 on the COSMAC VIP, whose interpreter occupies the start of the usual
+    /// program space
+    pub const CHIP_8_HIRES: Self = Self {
+        name: "CHIP-8 hi-res",
+        screen_height: 64,
+        start_addr: 0x2C0,
+        instruction_set: InstructionSet::Chip8HiRes,
+        ..Self::CHIP_8
+    };
+
+    /// CHIP-8 on the ETI-660, whose interpreter and display buffer occupy the first 1.5 KiB
+    pub const ETI_660: Self = Self {
+        name: "ETI-660",
+        screen_height: 48,
+        start_addr: 0x600,
+        ..Self::CHIP_8
+    };
+
+    /// CHIP-48 on the HP 48 calculators
+    pub const CHIP_48: Self = Self {
+        name: "CHIP-48",
+        stack_size: 16,
+        quirks: Quirks::CHIP_48,
+        ..Self::CHIP_8
+    };
+
+    /// SUPER-CHIP 1.1 on the HP 48 calculators
+    pub const SUPER_CHIP: Self = Self {
+        name: "SUPER-CHIP",
+        instruction_set: InstructionSet::SuperChip,
+        quirks: Quirks::SUPER_CHIP,
+        ..Self::CHIP_48
+    };
+
+    /// XO-CHIP, as implemented by Octo
+    pub const XO_CHIP: Self = Self {
+        name: "XO-CHIP",
+        ram_size: XO_CHIP_RAM_SIZE,
+        instruction_set: InstructionSet::XoChip,
+        quirks: Quirks::MODERN,
+        ..Self::SUPER_CHIP
+    };
+
+    /// MEGA-CHIP, as implemented by Mega8
+    pub const MEGA_CHIP: Self = Self {
+        name: "MEGA-CHIP",
+        ram_size: MEGA_CHIP_RAM_SIZE,
+        instruction_set: InstructionSet::MegaChip,
+        ..Self::SUPER_CHIP
+    };
+}
+
+/// Platforms a machine can emulate, see [`Chip8::with_platform`](crate::Chip8::with_platform)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Platform {
+    /// CHIP-8 on the COSMAC VIP
+    #[default]
+    Chip8,
+
+    /// Two-page hi-res CHIP-8 on the COSMAC VIP, with a 64x64 display and programs starting at
+    /// `0x2C0`
+    Chip8HiRes,
+
+    /// CHIP-8 on the ETI-660, with a 64x48 display and programs starting at `0x600`
+    Eti660,
+
+    /// CHIP-48 on the HP 48 calculators
+    Chip48,
+
+    /// SUPER-CHIP 1.1 on the HP 48 calculators
+    SuperChip,
+
+    /// XO-CHIP, as implemented by Octo
+    XoChip,
+
+    /// MEGA-CHIP, as implemented by Mega8
+    MegaChip,
+}
+
+impl Platform {
+    /// Every platform, in chronological order
+    pub const ALL: [Self; 7] = [
+        Self::Chip8,
+        Self::Chip8HiRes,
+        Self::Eti660,
+        Self::Chip48,
+        Self::SuperChip,
+        Self::XoChip,
+        Self::MegaChip,
+    ];
+
+    /// Description of the platform
+    pub const fn spec(self) -> PlatformSpec {
+        match self {
+            Self::Chip8 => PlatformSpec::CHIP_8,
+            Self::Chip8HiRes => PlatformSpec::CHIP_8_HIRES,
+            Self::Eti660 => PlatformSpec::ETI_660,
+            Self::Chip48 => PlatformSpec::CHIP_48,
+            Self::SuperChip => PlatformSpec::SUPER_CHIP,
+            Self::XoChip => PlatformSpec::XO_CHIP,
+            Self::MegaChip => PlatformSpec::MEGA_CHIP,
+        }
+    }
+
+    /// Short name of the platform, as parsed by [`str::parse`]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Chip8 => "chip8",
+            Self::Chip8HiRes => "chip8-hires",
+            Self::Eti660 => "eti660",
+            Self::Chip48 => "chip48",
+            Self::SuperChip => "schip",
+            Self::XoChip => "xochip",
+            Self::MegaChip => "megachip",
+        }
+    }
+}
+
+impl std::str::FromStr for Platform {
+    type Err = ParseError;
+
+    /// Platform called `s`, see [`Platform::name`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|platform| platform.name() == s)
+            .ok_or_else(|| ParseError::UnknownPlatform { name: s.into() })
+    }
+}
@@ -0,0 +1,121 @@
+//! Behaviours that differ between CHIP-8 interpreters
+
+use crate::ParseError;
+
+/// Behaviours that differ between CHIP-8 interpreters, for ROMs that rely on one of them<br>
+/// Start from one of the presets and adjust individual quirks if needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// `8XY6` and `8XYE` shift `VX` in place instead of storing the shifted `VY` in `VX`
+    pub shift_vx: bool,
+
+    /// `8XY1`, `8XY2` and `8XY3` set `VF` to `00`
+    pub vf_reset: bool,
+
+    /// `BNNN` behaves like `BXNN`, jumping to address `XNN + VX` instead of `NNN + V0`
+    pub jump_vx: bool,
+
+    /// How much `FX55` and `FX65` increment `I` after transferring registers `V0` to `VX`
+    pub index_increment: IndexIncrement,
+
+    /// What `DXYN` does with the parts of a sprite that go past the edges of the screen
+    pub sprite_edges: SpriteEdges,
+
+    /// `DXYN` waits for the vertical blank interrupt, ending the current frame
+    pub display_wait: bool,
+}
+
+impl Quirks {
+    /// The original CHIP-8 interpreter on the COSMAC VIP
+    pub const COSMAC_VIP: Self = Self {
+        shift_vx: false,
+        vf_reset: true,
+        jump_vx: false,
+        index_increment: IndexIncrement::XPlusOne,
+        sprite_edges: SpriteEdges::Clip,
+        display_wait: true,
+    };
+
+    /// CHIP-48 on the HP-48 calculators
+    pub const CHIP_48: Self = Self {
+        shift_vx: true,
+        vf_reset: false,
+        jump_vx: true,
+        index_increment: IndexIncrement::X,
+        sprite_edges: SpriteEdges::Clip,
+        display_wait: false,
+    };
+
+    /// SUPER-CHIP 1.1 on the HP-48 calculators
+    pub const SUPER_CHIP: Self = Self {
+        shift_vx: true,
+        vf_reset: false,
+        jump_vx: true,
+        index_increment: IndexIncrement::Unchanged,
+        sprite_edges: SpriteEdges::Clip,
+        display_wait: false,
+    };
+
+    /// Octo and most modern interpreters, targeted by recent homebrew
+    pub const MODERN: Self = Self {
+        shift_vx: false,
+        vf_reset: false,
+        jump_vx: false,
+        index_increment: IndexIncrement::XPlusOne,
+        sprite_edges: SpriteEdges::Wrap,
+        display_wait: false,
+    };
+
+    /// Every preset with its short name, as parsed by [`str::parse`]
+    pub const PRESETS: [(&'static str, Self); 4] = [
+        ("vip", Self::COSMAC_VIP),
+        ("chip48", Self::CHIP_48),
+        ("schip", Self::SUPER_CHIP),
+        ("modern", Self::MODERN),
+    ];
+}
+
+impl std::str::FromStr for Quirks {
+    type Err = ParseError;
+
+    /// Preset called `s`, see [`Quirks::PRESETS`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::PRESETS
+            .into_iter()
+            .find(|&(name, _)| name == s)
+            .map(|(_, quirks)| quirks)
+            .ok_or_else(|| ParseError::UnknownQuirks { name: s.into() })
+    }
+}
+
+impl Default for Quirks {
+    /// Behave like the original interpreter
+    fn default() -> Self {
+        Self::COSMAC_VIP
+    }
+}
+
+/// How much `FX55` and `FX65` increment `I` after transferring registers `V0` to `VX`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexIncrement {
+    /// `I` is set to `I + X + 1`, like the COSMAC VIP
+    #[default]
+    XPlusOne,
+
+    /// `I` is set to `I + X`, like CHIP-48
+    X,
+
+    /// `I` is left unchanged, like SUPER-CHIP
+    Unchanged,
+}
+
+/// What `DXYN` does with the parts of a sprite that go past the edges of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpriteEdges {
+    /// The pixels past the edges are not drawn
+    #[default]
+    Clip,
+
+    /// The pixels past the edges are drawn on the opposite side of the screen
+    Wrap,
+}
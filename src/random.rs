@@ -0,0 +1,140 @@
+//! Random numbers drawn by `CXNN`
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// Source of the random numbers drawn by `CXNN`, see [`Chip8::set_random_source`]
+///
+/// [`Chip8::set_random_source`]: crate::Chip8::set_random_source
+pub trait RandomSource: fmt::Debug {
+    /// Next random byte
+    fn next_byte(&mut self) -> u8;
+
+    /// Seed that reproduces the sequence from its start, if the source has one
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// Number of bytes drawn since the start of the sequence, if the source counts them<br>
+    /// Together with [`RandomSource::seed`], it reproduces the sequence from the current point
+    fn draws(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Pseudo-random numbers generated from a seed, the same seed always giving the same sequence
+#[derive(Debug, Clone)]
+pub struct SeededRandom {
+    /// Seed the generator started from
+    seed: u64,
+
+    /// Number of bytes drawn since the seed
+    draws: u64,
+
+    /// Generator
+    rng: StdRng,
+}
+
+impl SeededRandom {
+    /// Create a generator starting from `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            draws: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Create a generator starting from `seed` and skip its first `draws` bytes, resuming a
+    /// sequence where [`RandomSource::draws`] was taken
+    pub fn resume(seed: u64, draws: u64) -> Self {
+        let mut random = Self::new(seed);
+        for _ in 0..draws {
+            random.next_byte();
+        }
+        random
+    }
+
+    /// Create a generator starting from a random seed, see [`RandomSource::seed`] to retrieve it
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl Default for SeededRandom {
+    /// Start from a random seed
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl RandomSource for SeededRandom {
+    fn next_byte(&mut self) -> u8 {
+        self.draws += 1;
+        self.rng.gen()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn draws(&self) -> Option<u64> {
+        Some(self.draws)
+    }
+}
+
+/// Pseudo-random numbers generated the way the COSMAC VIP interpreter does: a pointer walks through
+/// a page of the interpreter code, and each byte it reaches is added to the previous number<br>
+/// The numbers are only as random as the bytes of the page, which should be taken from a dump of the
+/// interpreter for authentic sequences<br>
+/// No dump ships with this crate, so frontends have to be given one
+#[derive(Debug, Clone)]
+pub struct VipRandom {
+    /// Bytes the pointer walks through
+    page: [u8; 256],
+
+    /// Position of the pointer when the generator was created
+    seed: u8,
+
+    /// Position of the pointer in the page
+    pointer: u8,
+
+    /// Last number generated
+    value: u8,
+
+    /// Number of bytes drawn since the seed
+    draws: u64,
+}
+
+impl VipRandom {
+    /// Create a generator walking through `page` with its pointer starting at `seed`<br>
+    /// Only the page at 0x0100 of the interpreter, as loaded into RAM from tape, gives the
+    /// authentic numbers; any other page, like a blank one, only gives a predictable sequence
+    pub fn new(page: [u8; 256], seed: u8) -> Self {
+        Self {
+            page,
+            seed,
+            pointer: seed,
+            value: 0,
+            draws: 0,
+        }
+    }
+}
+
+impl RandomSource for VipRandom {
+    fn next_byte(&mut self) -> u8 {
+        self.draws += 1;
+        self.pointer = self.pointer.wrapping_add(1);
+        self.value = self.value.wrapping_add(self.page[self.pointer as usize]);
+        self.value
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed as u64)
+    }
+
+    fn draws(&self) -> Option<u64> {
+        Some(self.draws)
+    }
+}
@@ -0,0 +1,130 @@
+//! Framebuffer of the display
+
+/// Bitplane drawn on by CHIP-8 and SUPER-CHIP programs
+pub(crate) const FIRST_PLANE: u8 = 0b01;
+
+/// Number of bitplanes of the XO-CHIP display
+pub const PLANES_COUNT: usize = 2;
+
+/// Every bit of a pixel, for MEGA-CHIP palette indices
+const ALL_PLANES: u8 = 0xFF;
+
+/// Framebuffer of the display, one byte per pixel, row by row<br>
+/// Bit `N` of a pixel is set when it is lit on plane `N`, except in MEGA-CHIP mode where pixels
+/// hold palette indices and come with their ARGB colour
+#[derive(Debug, Clone)]
+pub(crate) struct Screen {
+    /// Width, in pixels
+    pub(crate) width: usize,
+
+    /// Height, in pixels
+    pub(crate) height: usize,
+
+    /// Pixels, row by row
+    pub(crate) pixels: Vec<u8>,
+
+    /// Bitplanes affected by drawing, clearing and scrolling, as a mask
+    pub(crate) planes: u8,
+
+    /// ARGB colour of each pixel in MEGA-CHIP mode, empty otherwise
+    pub(crate) colors: Vec<u32>,
+}
+
+impl Screen {
+    /// Create a blank screen of `width` by `height` pixels, drawing on the first plane
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height],
+            planes: FIRST_PLANE,
+            colors: Vec::new(),
+        }
+    }
+
+    /// Create a blank MEGA-CHIP screen of `width` by `height` pixels, whose pixels are palette
+    /// indices with an ARGB colour
+    pub(crate) fn with_colors(width: usize, height: usize) -> Self {
+        Self {
+            planes: ALL_PLANES,
+            colors: vec![0; width * height],
+            ..Self::new(width, height)
+        }
+    }
+
+    /// Whether the pixels have an ARGB colour, in MEGA-CHIP mode
+    pub(crate) fn has_colors(&self) -> bool {
+        !self.colors.is_empty()
+    }
+
+    /// Turn every pixel of the selected planes off
+    pub(crate) fn clear(&mut self) {
+        for pixel in &mut self.pixels {
+            *pixel &= !self.planes;
+        }
+        self.colors.fill(0);
+    }
+
+    /// Change the resolution, turning every pixel of every plane off
+    pub(crate) fn resize(&mut self, width: usize, height: usize) {
+        *self = if self.has_colors() {
+            Self::with_colors(width, height)
+        } else {
+            Self {
+                planes: self.planes,
+                ..Self::new(width, height)
+            }
+        };
+    }
+
+    /// Flip the pixel at (`x`, `y`) on `plane` and return whether it was turned off
+    pub(crate) fn toggle(&mut self, x: usize, y: usize, plane: u8) -> bool {
+        let pixel = &mut self.pixels[x + y * self.width];
+        *pixel ^= plane;
+        *pixel & plane == 0
+    }
+
+    /// Move the content of the selected planes down by `n` rows, leaving blank rows at the top
+    pub(crate) fn scroll_down(&mut self, n: usize) {
+        self.shift(0, n as isize);
+    }
+
+    /// Move the content of the selected planes up by `n` rows, leaving blank rows at the bottom
+    pub(crate) fn scroll_up(&mut self, n: usize) {
+        self.shift(0, -(n as isize));
+    }
+
+    /// Move the content of the selected planes right by `n` columns, leaving blank columns on the
+    /// left
+    pub(crate) fn scroll_right(&mut self, n: usize) {
+        self.shift(n as isize, 0);
+    }
+
+    /// Move the content of the selected planes left by `n` columns, leaving blank columns on the
+    /// right
+    pub(crate) fn scroll_left(&mut self, n: usize) {
+        self.shift(-(n as isize), 0);
+    }
+
+    /// Move the content of the selected planes by `dx` columns and `dy` rows, leaving the other
+    /// planes untouched
+    fn shift(&mut self, dx: isize, dy: isize) {
+        let source = self.pixels.clone();
+        let source_colors = self.colors.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (src_x, src_y) = (x as isize - dx, y as isize - dy);
+                let in_bounds = (0..self.width as isize).contains(&src_x)
+                    && (0..self.height as isize).contains(&src_y);
+                let src = in_bounds.then(|| src_x as usize + src_y as usize * self.width);
+
+                let index = x + y * self.width;
+                let moved = src.map_or(0, |src| source[src] & self.planes);
+                self.pixels[index] = (self.pixels[index] & !self.planes) | moved;
+                if self.has_colors() {
+                    self.colors[index] = src.map_or(0, |src| source_colors[src]);
+                }
+            }
+        }
+    }
+}
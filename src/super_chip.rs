@@ -0,0 +1,94 @@
+//! Instructions added by SUPER-CHIP 1.1
+
+use crate::{
+    Chip8, Chip8Error, HIRES_SCREEN_HEIGHT, HIRES_SCREEN_WIDTH, LARGE_FONT_SPRITE_SIZE, OP_LENGTH,
+};
+
+/// Number of RPL user flags
+pub const RPL_FLAGS_COUNT: usize = 16;
+
+/// Columns scrolled by `00FB` and `00FC`
+const HORIZONTAL_SCROLL: usize = 4;
+
+impl Chip8 {
+    /// op: `00CN`
+    /// Scroll the display (the selected planes with XO-CHIP) down by `N` pixels
+    pub(crate) fn scroll_down(&mut self, op: u16) {
+        let n = (op & 0x000F) as usize;
+        self.screen.scroll_down(n);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `00FB`
+    /// Scroll the display (the selected planes with XO-CHIP) right by 4 pixels
+    pub(crate) fn scroll_right(&mut self) {
+        self.screen.scroll_right(HORIZONTAL_SCROLL);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `00FC`
+    /// Scroll the display (the selected planes with XO-CHIP) left by 4 pixels
+    pub(crate) fn scroll_left(&mut self) {
+        self.screen.scroll_left(HORIZONTAL_SCROLL);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `00FD`
+    /// Exit the interpreter<br>
+    /// `PC` stays on this instruction and no other instruction is executed until the machine is reset
+    pub(crate) fn exit(&mut self) {
+        self.exited = true;
+    }
+
+    /// op: `00FE`
+    /// Switch to the low resolution display of the platform (64x32) and clear it
+    pub(crate) fn low_res(&mut self) {
+        let spec = self.platform.spec();
+        self.screen.resize(spec.screen_width, spec.screen_height);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `00FF`
+    /// Switch to the high resolution display (128x64) and clear it
+    pub(crate) fn high_res(&mut self) {
+        self.screen.resize(HIRES_SCREEN_WIDTH, HIRES_SCREEN_HEIGHT);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX30`
+    /// Set `I` to the memory address of the large sprite data corresponding to the hexadecimal digit
+    /// stored in register `VX`
+    pub(crate) fn set_i_to_large_font(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let digit = (self.registers[vx] & 0x0F) as u32;
+        self.register_i = self.large_font_addr() + digit * LARGE_FONT_SPRITE_SIZE as u32;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `FX75`
+    /// Store the values of registers `V0` to `VX` inclusive in the RPL user flags
+    pub(crate) fn store_rpl_flags(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let x = self.rpl_flags_range(op)?;
+        self.rpl_flags[..=x].copy_from_slice(&self.registers[..=x]);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `FX85`
+    /// Fill registers `V0` to `VX` inclusive with the values stored in the RPL user flags
+    pub(crate) fn load_rpl_flags(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let x = self.rpl_flags_range(op)?;
+        self.registers[..=x].copy_from_slice(&self.rpl_flags[..=x]);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// `X` of an `FX75` or `FX85` instruction, which SUPER-CHIP limits to 7 and XO-CHIP to 15
+    fn rpl_flags_range(&self, op: u16) -> Result<usize, Chip8Error> {
+        let x = ((op & 0x0F00) >> 8) as usize;
+        if x >= self.instruction_set().rpl_flags_count() {
+            return Err(Chip8Error::InvalidOpcode { pc: self.pc, op });
+        }
+        Ok(x)
+    }
+}
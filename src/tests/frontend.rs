@@ -0,0 +1,117 @@
+//! Driving a machine through a frontend
+
+use super::*;
+use crate::frontend::{parse_option, Driver, Frontend};
+
+/// Frontend replaying scripted key events and recording what the driver tells it
+#[derive(Debug, Default)]
+struct Scripted {
+    /// Key events of the next frames, the user quitting once they run out
+    frames: Vec<[Option<KeyState>; KEYS_COUNT]>,
+
+    /// Number of frames presented
+    presented: usize,
+
+    /// State of the beeper at each frame
+    beeper: Vec<bool>,
+
+    /// Errors the machine halted with, `None` for an exit
+    halts: Vec<Option<Chip8Error>>,
+}
+
+impl Scripted {
+    /// Frontend quitting after `count` frames without key events
+    fn idle(count: usize) -> Self {
+        Self {
+            frames: vec![Default::default(); count],
+            ..Self::default()
+        }
+    }
+}
+
+impl Frontend for Scripted {
+    fn poll_keys(&mut self) -> Option<[Option<KeyState>; KEYS_COUNT]> {
+        (!self.frames.is_empty()).then(|| self.frames.remove(0))
+    }
+
+    fn present(&mut self, _chip8: &Chip8) {
+        self.presented += 1;
+    }
+
+    fn set_beeper(&mut self, on: bool) {
+        self.beeper.push(on);
+    }
+
+    fn halted(&mut self, _chip8: &Chip8, error: Option<&Chip8Error>) {
+        self.halts.push(error.cloned());
+    }
+}
+
+#[test]
+fn driver_runs_until_quit() {
+    // Loop
+    let mut driver = Driver::new(machine(&[0x1200]), Scripted::idle(3));
+    let mut frames = 0;
+    while driver.frame() {
+        frames += 1;
+    }
+
+    assert_eq!(frames, 3);
+    assert_eq!(driver.frontend().presented, 3);
+    assert!(!driver.is_halted());
+}
+
+#[test]
+fn driver_forwards_keys() {
+    // Wait for a key in V0, loop
+    let mut frontend = Scripted::idle(2);
+    frontend.frames[1][0xA] = Some(KeyState::Pressed);
+    let mut driver = Driver::new(machine(&[0xF00A, 0x1202]), frontend);
+    while driver.frame() {}
+
+    assert_eq!(driver.chip8().registers[0], 0xA);
+}
+
+#[test]
+fn driver_sounds_beeper() {
+    // V0 = 2, sound timer = V0, loop
+    let mut driver = Driver::new(machine(&[0x6002, 0xF018, 0x1204]), Scripted::idle(4));
+    while driver.frame() {}
+
+    assert_eq!(driver.frontend().beeper, [true, false, false, false]);
+}
+
+#[test]
+fn driver_halts_on_exit_and_error() {
+    // Exit
+    let mut driver = Driver::new(
+        platform_machine(Platform::SuperChip, &[0x00FD]),
+        Scripted::idle(3),
+    );
+    while driver.frame() {}
+    assert!(driver.is_halted());
+    assert_eq!(driver.frontend().halts, [None]);
+    assert_eq!(driver.frontend().presented, 3);
+
+    // Return outside of a subroutine
+    let mut driver = Driver::new(machine(&[0x00EE]), Scripted::idle(3));
+    while driver.frame() {}
+    assert_eq!(
+        driver.frontend().halts,
+        [Some(Chip8Error::StackUnderflow { pc: 0x200 })]
+    );
+}
+
+#[test]
+fn options_parse_with_their_names_in_errors() {
+    assert_eq!(parse_option("--speed", "1000"), Ok(1000u32));
+    assert_eq!(parse_option("--platform", "schip"), Ok(Platform::SuperChip));
+    assert_eq!(
+        parse_option::<u32>("--speed", "fast"),
+        Err("Invalid `--speed fast`: invalid digit found in string".into())
+    );
+    assert_eq!(
+        parse_option::<Quirks>("--quirks", "octo"),
+        Err("Invalid `--quirks octo`: unknown quirks preset `octo`".into())
+    );
+}
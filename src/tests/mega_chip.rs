@@ -0,0 +1,220 @@
+//! MEGA-CHIP instructions
+
+use super::*;
+
+/// Address at which the tests store palettes
+const PALETTE_ADDR: u16 = 0x400;
+
+/// Create a MEGA-CHIP machine with `program` loaded at the start address and `colors` stored at
+/// [`PALETTE_ADDR`]
+fn mega_chip_machine(program: &[u16], colors: &[u32]) -> Chip8 {
+    let mut chip8 = platform_machine(Platform::MegaChip, program);
+    let bytes: Vec<u8> = colors
+        .iter()
+        .flat_map(|color| color.to_be_bytes())
+        .collect();
+    let start = PALETTE_ADDR as usize;
+    chip8.ram[start..start + bytes.len()].copy_from_slice(&bytes);
+    chip8
+}
+
+/// Program that turns MEGA-CHIP mode on, loads `colors` colours from [`PALETTE_ADDR`], sets 2x1
+/// sprites and points `I` at [`SPRITE_ADDR`]
+fn mega_chip_setup(colors: u16) -> Vec<u16> {
+    vec![
+        0x0011,
+        0xA000 | PALETTE_ADDR,
+        0x0200 | colors,
+        0x0302,
+        0x0401,
+        0xA000 | SPRITE_ADDR,
+    ]
+}
+
+#[test]
+fn mega_chip_mode() {
+    let mut chip8 = mega_chip_machine(&[0x0011, 0x0010], &[]);
+    assert_eq!(chip8.screen_colors(), None);
+
+    run(&mut chip8, 1);
+    assert_eq!((chip8.screen_width(), chip8.screen_height()), (256, 192));
+    assert_eq!(chip8.screen_colors().map(<[u32]>::len), Some(256 * 192));
+
+    run(&mut chip8, 1);
+    assert_eq!((chip8.screen_width(), chip8.screen_height()), (64, 32));
+    assert_eq!(chip8.screen_colors(), None);
+}
+
+#[test]
+fn mega_chip_instructions_need_mega_chip() {
+    for op in [0x0011, 0x0200, 0x0700] {
+        let mut chip8 = platform_machine(Platform::SuperChip, &[op]);
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::UnsupportedOpcode { pc: 0x200, op }),
+            "{op:04X}"
+        );
+    }
+}
+
+#[test]
+fn set_i_24_bits() {
+    // I = 0x123456, skip if V0 == 0, I = 0x000001, V1 = 1
+    let mut chip8 = mega_chip_machine(&[0x0112, 0x3456, 0x3000, 0x0100, 0x0001, 0x6101], &[]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.register_i, 0x12_3456);
+    assert_eq!(chip8.registers[1], 1);
+}
+
+#[test]
+fn i_is_24_bits_wide() {
+    // I = 0x00FFFF, V0 = 2, I += V0
+    let mut chip8 = mega_chip_machine(&[0x0100, 0xFFFF, 0x6002, 0xF01E], &[]);
+    run(&mut chip8, 3);
+    assert_eq!(chip8.register_i, 0x1_0001);
+
+    // The other platforms keep a 16-bit I
+    let mut chip8 = platform_machine(Platform::XoChip, &[0xF000, 0xFFFF, 0x6002, 0xF01E]);
+    run(&mut chip8, 3);
+    assert_eq!(chip8.register_i, 0x0001);
+}
+
+#[test]
+fn font_at_last_16_bit_address() {
+    // V0 = 0xF, I = font sprite of V0, I = large font sprite of V0
+    let mut chip8 = mega_chip_machine(&[0x600F, 0xF029, 0xF030], &[]);
+    chip8.set_font(FontSet::Octo, 0xFFFF).unwrap();
+    run(&mut chip8, 2);
+
+    // The font spans past 0xFFFF, which only the 24-bit I can reach
+    let glyph = 0xFFFF + 0xF * FONT_SPRITE_SIZE as u32;
+    assert_eq!(chip8.register_i, glyph);
+    assert_eq!(
+        chip8.ram[glyph as usize..glyph as usize + 5],
+        FontSet::Octo.glyphs()[75..80]
+    );
+
+    run(&mut chip8, 1);
+    let large_glyph = 0xFFFF + FONT_SIZE as u32 + 0xF * LARGE_FONT_SPRITE_SIZE as u32;
+    assert_eq!(chip8.register_i, large_glyph);
+    assert_eq!(
+        chip8.ram[large_glyph as usize..large_glyph as usize + 10],
+        LARGE_FONT[150..160]
+    );
+}
+
+#[test]
+fn draw_color_sprite() {
+    let mut program = mega_chip_setup(2);
+    // V0 = 10, V1 = 20, draw
+    program.extend([0x600A, 0x6114, 0xD010]);
+    let mut chip8 = mega_chip_machine(&program, &[0xFF11_2233, 0xFF44_5566]);
+    chip8.ram[SPRITE_ADDR as usize..SPRITE_ADDR as usize + 2].copy_from_slice(&[2, 1]);
+    run(&mut chip8, program.len());
+
+    let pixel = 10 + 20 * MEGA_CHIP_SCREEN_WIDTH;
+    assert_eq!(chip8.screen()[pixel..pixel + 2], [2, 1]);
+    let colors = chip8.screen_colors().unwrap();
+    assert_eq!(colors[pixel..pixel + 2], [0xFF44_5566, 0xFF11_2233]);
+    assert_eq!(chip8.registers[0xF], 0);
+}
+
+#[test]
+fn color_sprite_transparency_and_clipping() {
+    let mut program = mega_chip_setup(1);
+    // V0 = 255, draw at (255, 0)
+    program.extend([0x60FF, 0xD000]);
+    let mut chip8 = mega_chip_machine(&program, &[0xFFFF_FFFF]);
+    chip8.ram[SPRITE_ADDR as usize..SPRITE_ADDR as usize + 2].copy_from_slice(&[0, 1]);
+    run(&mut chip8, program.len());
+
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+}
+
+#[test]
+fn color_sprite_collision() {
+    let mut program = mega_chip_setup(2);
+    // Collision colour = 1, draw, draw again
+    program.extend([0x0901, 0xD000, 0xD000]);
+    let mut chip8 = mega_chip_machine(&program, &[0xFFFF_FFFF, 0xFF00_0000]);
+    chip8.ram[SPRITE_ADDR as usize..SPRITE_ADDR as usize + 2].copy_from_slice(&[1, 2]);
+
+    run(&mut chip8, program.len() - 1);
+    assert_eq!(chip8.registers[0xF], 0);
+    run(&mut chip8, 1);
+    assert_eq!(chip8.registers[0xF], 1);
+}
+
+#[test]
+fn blend_modes() {
+    for (mode, expected) in [
+        (0x0800, 0xFF80_4000),
+        (0x0802, 0xFF60_607F),
+        (0x0804, 0xFFC0_C0FF),
+        (0x0805, 0xFF20_2000),
+    ] {
+        let mut program = mega_chip_setup(2);
+        // Draw colour 1, blend mode, I = sprite + 2, draw colour 2
+        program.extend([0xD000, mode, 0xA002 | SPRITE_ADDR, 0xD000]);
+        let mut chip8 = mega_chip_machine(&program, &[0xFF40_80FF, 0xFF80_4000]);
+        chip8.ram[SPRITE_ADDR as usize..SPRITE_ADDR as usize + 4].copy_from_slice(&[1, 0, 2, 0]);
+        run(&mut chip8, program.len());
+
+        assert_eq!(chip8.screen_colors().unwrap()[0], expected, "{mode:04X}");
+    }
+}
+
+#[test]
+fn scroll_colors() {
+    let mut program = mega_chip_setup(1);
+    // Draw, scroll down 1, scroll up 1 with 00BN
+    program.extend([0xD000, 0x00C1, 0x00B1]);
+    let mut chip8 = mega_chip_machine(&program, &[0xFF12_3456]);
+    chip8.ram[SPRITE_ADDR as usize] = 1;
+
+    run(&mut chip8, program.len() - 1);
+    let colors = chip8.screen_colors().unwrap();
+    assert_eq!(colors[MEGA_CHIP_SCREEN_WIDTH], 0xFF12_3456);
+    assert_eq!(colors[0], 0);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.screen_colors().unwrap()[0], 0xFF12_3456);
+    assert_eq!(chip8.screen()[0], 1);
+}
+
+#[test]
+fn digitised_sound() {
+    // I = 0x300, play once, stop
+    let mut chip8 = mega_chip_machine(&[0xA300, 0x0601, 0x0700], &[]);
+    chip8.ram[0x300..0x30A].copy_from_slice(&[0x1F, 0x40, 0, 0, 4, 0, 1, 2, 3, 4]);
+
+    run(&mut chip8, 2);
+    assert_eq!(
+        chip8.digitised_sound(),
+        Some(DigitisedSound {
+            samples: &[1, 2, 3, 4],
+            sample_rate: 8000,
+            looping: false
+        })
+    );
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.digitised_sound(), None);
+}
+
+#[test]
+fn digitised_sound_past_end_of_ram() {
+    // I = 0x300, play in a loop
+    let mut chip8 = mega_chip_machine(&[0xA300, 0x0600], &[]);
+    chip8.ram[0x302..0x305].copy_from_slice(&[0xFF, 0xFF, 0xFF]);
+    run(&mut chip8, 1);
+
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0x202,
+            addr: MEGA_CHIP_RAM_SIZE as u32
+        })
+    );
+}
@@ -0,0 +1,414 @@
+use super::*;
+
+mod frontend;
+mod mega_chip;
+mod opcodes;
+mod platform;
+mod super_chip;
+mod xo_chip;
+
+/// Address at which the tests store sprite data
+const SPRITE_ADDR: u16 = 0x300;
+
+/// Create a machine with `program` loaded at the start address
+fn machine(program: &[u16]) -> Chip8 {
+    platform_machine(Platform::Chip8, program)
+}
+
+/// Create a machine emulating `platform` with `program` loaded at the start address
+fn platform_machine(platform: Platform, program: &[u16]) -> Chip8 {
+    let rom: Vec<u8> = program.iter().flat_map(|op| op.to_be_bytes()).collect();
+    let mut chip8 = Chip8::with_platform(platform);
+    chip8.load_cartridge(&rom).unwrap();
+    chip8
+}
+
+/// Create a machine that draws `sprite` with `DXYN` at each of `positions` in turn
+fn drawing_machine(sprite: &[u8], positions: &[(u8, u8)]) -> Chip8 {
+    let n = sprite.len() as u16;
+    let mut program = vec![0xA000 | SPRITE_ADDR];
+    for &(x, y) in positions {
+        program.extend([0x6000 | x as u16, 0x6100 | y as u16, 0xD010 | n]);
+    }
+
+    let mut chip8 = machine(&program);
+    let start = SPRITE_ADDR as usize;
+    chip8.ram[start..start + sprite.len()].copy_from_slice(sprite);
+    chip8
+}
+
+/// Execute `count` instructions
+fn run(chip8: &mut Chip8, count: usize) {
+    for _ in 0..count {
+        chip8.step().unwrap();
+    }
+}
+
+/// Emulate one frame
+fn run_frame(chip8: &mut Chip8) {
+    chip8.run_frame().unwrap();
+}
+
+/// Render `h` rows of `w` pixels starting at (`x`, `y`), with `#` for lit pixels and `.` otherwise
+fn screen_rect(chip8: &Chip8, x: usize, y: usize, w: usize, h: usize) -> Vec<String> {
+    (y..y + h)
+        .map(|y| {
+            (x..x + w)
+                .map(|x| match chip8.screen()[x + y * chip8.screen_width()] {
+                    0 => '.',
+                    _ => '#',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn empty_cartridge_is_rejected() {
+    let mut chip8 = Chip8::default();
+
+    assert_eq!(chip8.load_cartridge(&[]), Err(CartridgeError::Empty));
+}
+
+#[test]
+fn cartridge_must_fit_in_ram() {
+    let max = RAM_SIZE - CARTRIDGE_START_ADDR as usize;
+    let mut chip8 = Chip8::default();
+
+    assert_eq!(
+        chip8.load_cartridge(&vec![0xAA; max + 1]),
+        Err(CartridgeError::TooLarge { size: max + 1, max })
+    );
+    // RAM is left untouched
+    assert_eq!(chip8.ram[CARTRIDGE_START_ADDR as usize], 0);
+
+    assert_eq!(chip8.load_cartridge(&vec![0xAA; max]), Ok(()));
+    assert_eq!(chip8.ram[RAM_SIZE - 1], 0xAA);
+}
+
+#[test]
+fn font_sets() {
+    for font in [FontSet::CosmacVip, FontSet::Eti660, FontSet::Dream6800] {
+        // V1 = 0xA, I = font sprite of V1
+        let mut chip8 = machine(&[0x610A, 0xF129]);
+        chip8.set_font(font, FONT_SPRITES_ADDR).unwrap();
+        run(&mut chip8, 2);
+
+        let glyph = (FONT_SPRITES_ADDR + 0xA * FONT_SPRITE_SIZE) as usize;
+        assert_eq!(chip8.register_i, glyph as u32, "{font:?}");
+        assert_eq!(
+            chip8.ram[glyph..glyph + 5],
+            font.glyphs()[50..55],
+            "{font:?}"
+        );
+        assert_ne!(font.glyphs(), FontSet::default().glyphs(), "{font:?}");
+    }
+}
+
+#[test]
+fn custom_font_must_have_16_glyphs() {
+    let glyphs: Vec<u8> = (0..FONT_SIZE as u8).collect();
+    let font = FontSet::from_bytes(&glyphs).unwrap();
+    assert_eq!(font.glyphs()[..], glyphs[..]);
+
+    for size in [0, FONT_SIZE - 1, FONT_SIZE + 1] {
+        assert_eq!(
+            FontSet::from_bytes(&vec![0; size]),
+            Err(FontError::InvalidSize { size })
+        );
+    }
+}
+
+#[test]
+fn font_at_custom_address() {
+    let font = FontSet::from_bytes(&[0xAA; FONT_SIZE]).unwrap();
+    // V1 = 0x3, I = font sprite of V1
+    let mut chip8 = machine(&[0x6103, 0xF129]);
+    chip8.set_font(font, 0x100).unwrap();
+    run(&mut chip8, 2);
+
+    assert_eq!(chip8.register_i, 0x100 + 3 * FONT_SPRITE_SIZE as u32);
+    assert_eq!(chip8.ram[0x100..0x100 + FONT_SIZE], [0xAA; FONT_SIZE]);
+    // The previous font is erased
+    let old = FONT_SPRITES_ADDR as usize;
+    assert!(chip8.ram[old..old + FONT_SIZE]
+        .iter()
+        .all(|&byte| byte == 0));
+}
+
+#[test]
+fn font_out_of_bounds() {
+    let mut chip8 = machine(&[0x00E0]);
+    let addr = (RAM_SIZE - FONT_SIZE - LARGE_FONT_SIZE + 1) as u16;
+
+    assert_eq!(
+        chip8.set_font(FontSet::CosmacVip, addr),
+        Err(FontError::OutOfBounds { addr })
+    );
+    // The font in use is untouched
+    let start = FONT_SPRITES_ADDR as usize;
+    assert_eq!(
+        chip8.ram[start..start + FONT_SIZE],
+        *FontSet::default().glyphs()
+    );
+    assert!(chip8.set_font(FontSet::CosmacVip, addr - 1).is_ok());
+}
+
+#[test]
+fn font_is_kept_across_reset() {
+    let mut chip8 = machine(&[0x00E0]);
+    chip8.set_font(FontSet::Eti660, 0x100).unwrap();
+    chip8.reset();
+
+    assert_eq!(
+        chip8.ram[0x100..0x100 + FONT_SIZE],
+        *FontSet::Eti660.glyphs()
+    );
+    let old = FONT_SPRITES_ADDR as usize;
+    assert!(chip8.ram[old..old + FONT_SIZE]
+        .iter()
+        .all(|&byte| byte == 0));
+}
+
+#[test]
+fn draw_sprite() {
+    let mut chip8 = drawing_machine(&[0xF0, 0x90], &[(1, 2)]);
+    run(&mut chip8, 4);
+
+    assert_eq!(screen_rect(&chip8, 0, 2, 6, 2), [".####.", ".#..#."]);
+    assert_eq!(chip8.screen().iter().filter(|&&px| px == 1).count(), 6);
+    assert_eq!(chip8.registers[0xF], 0);
+}
+
+#[test]
+fn draw_same_sprite_twice_erases_it() {
+    let mut chip8 = drawing_machine(&[0xF0, 0x90], &[(1, 2), (1, 2)]);
+    run(&mut chip8, 7);
+
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+    assert_eq!(chip8.registers[0xF], 1);
+}
+
+#[test]
+fn draw_overlapping_sprites() {
+    let mut chip8 = drawing_machine(&[0xFF, 0x81], &[(0, 0), (4, 1)]);
+    run(&mut chip8, 7);
+
+    assert_eq!(
+        screen_rect(&chip8, 0, 0, 13, 3),
+        ["########.....", "#...###.####.", "....#......#."]
+    );
+    assert_eq!(chip8.registers[0xF], 1);
+}
+
+#[test]
+fn draw_without_collision_clears_vf() {
+    let mut chip8 = drawing_machine(&[0xFF], &[(0, 0), (0, 0), (8, 0)]);
+    run(&mut chip8, 7);
+    assert_eq!(chip8.registers[0xF], 1);
+
+    run(&mut chip8, 3);
+    assert_eq!(screen_rect(&chip8, 0, 0, 16, 1), ["........########"]);
+    assert_eq!(chip8.registers[0xF], 0);
+}
+
+#[test]
+fn draw_position_wraps_around() {
+    let x = (SCREEN_WIDTH + 2) as u8;
+    let y = (SCREEN_HEIGHT + 1) as u8;
+    let mut chip8 = drawing_machine(&[0xC0], &[(x, y)]);
+    run(&mut chip8, 4);
+
+    assert_eq!(screen_rect(&chip8, 0, 0, 5, 2), [".....", "..##."]);
+}
+
+#[test]
+fn draw_clips_at_edges() {
+    let x = (SCREEN_WIDTH - 2) as u8;
+    let y = (SCREEN_HEIGHT - 1) as u8;
+    let mut chip8 = drawing_machine(&[0xFF, 0xFF], &[(x, y)]);
+    run(&mut chip8, 4);
+
+    assert_eq!(screen_rect(&chip8, x as usize, y as usize, 2, 1), ["##"]);
+    assert_eq!(chip8.screen().iter().filter(|&&px| px == 1).count(), 2);
+}
+
+#[test]
+fn draw_wraps_at_edges() {
+    let x = (SCREEN_WIDTH - 2) as u8;
+    let y = (SCREEN_HEIGHT - 1) as u8;
+    let mut chip8 = drawing_machine(&[0xFF, 0x81], &[(x, y)]);
+    let mut quirks = chip8.quirks();
+    quirks.sprite_edges = SpriteEdges::Wrap;
+    chip8.set_quirks(quirks);
+    run(&mut chip8, 4);
+
+    assert_eq!(screen_rect(&chip8, x as usize, y as usize, 2, 1), ["##"]);
+    assert_eq!(screen_rect(&chip8, 0, y as usize, 6, 1), ["######"]);
+    assert_eq!(screen_rect(&chip8, x as usize, 0, 2, 1), ["#."]);
+    assert_eq!(screen_rect(&chip8, 0, 0, 6, 1), [".....#"]);
+    assert_eq!(chip8.registers[0xF], 0);
+}
+
+#[test]
+fn store_bcd() {
+    // V0 = 254, I = 0x300, FX33
+    let mut chip8 = machine(&[0x60FE, 0xA300, 0xF033]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.ram[0x300..0x303], [2, 5, 4]);
+    assert_eq!(chip8.register_i, 0x300);
+}
+
+#[test]
+fn store_bcd_wraps_around_ram() {
+    // V0 = 7, I = 0xFFF, FX33
+    let mut chip8 = machine(&[0x6007, 0xAFFF, 0xF033]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.ram[RAM_SIZE - 1], 0);
+    assert_eq!(chip8.ram[0..2], [0, 7]);
+}
+
+#[test]
+fn store_and_restore_registers_increment_i() {
+    for (increment, i_after_store, i_after_restore) in [
+        (IndexIncrement::XPlusOne, 0x303, 0x303),
+        (IndexIncrement::X, 0x302, 0x302),
+        (IndexIncrement::Unchanged, 0x300, 0x301),
+    ] {
+        // V0 = 1, V1 = 2, V2 = 3, I = 0x300, F255, I = 0x301, F165
+        let mut chip8 = machine(&[0x6001, 0x6102, 0x6203, 0xA300, 0xF255, 0xA301, 0xF165]);
+        let mut quirks = chip8.quirks();
+        quirks.index_increment = increment;
+        chip8.set_quirks(quirks);
+
+        run(&mut chip8, 5);
+        assert_eq!(chip8.ram[0x300..0x304], [1, 2, 3, 0], "{increment:?}");
+        assert_eq!(chip8.register_i, i_after_store, "{increment:?}");
+
+        run(&mut chip8, 2);
+        assert_eq!(chip8.registers[0..3], [2, 3, 3], "{increment:?}");
+        assert_eq!(chip8.register_i, i_after_restore, "{increment:?}");
+    }
+}
+
+#[test]
+fn store_registers_wraps_around_ram() {
+    // V0 = 1, V1 = 2, I = 0xFFF, F155
+    let mut chip8 = machine(&[0x6001, 0x6102, 0xAFFF, 0xF155]);
+    run(&mut chip8, 4);
+
+    assert_eq!(chip8.ram[RAM_SIZE - 1], 1);
+    assert_eq!(chip8.ram[0], 2);
+}
+
+#[test]
+fn run_frame_executes_instructions_at_clock_speed() {
+    // V0 += 1, forever
+    let mut chip8 = machine(&[0x7001, 0x1200]);
+    chip8.set_clock_hz(90);
+
+    run_frame(&mut chip8);
+    assert_eq!(chip8.registers[0], 1);
+    run_frame(&mut chip8);
+    assert_eq!(chip8.registers[0], 2);
+
+    chip8.set_instructions_per_frame(10);
+    run_frame(&mut chip8);
+    assert_eq!(chip8.registers[0], 7);
+}
+
+#[test]
+fn fastest_clock_speed_is_capped() {
+    // Exit
+    let mut chip8 = platform_machine(Platform::SuperChip, &[0x00FD]);
+    chip8.set_instructions_per_frame(100_000_000);
+    assert_eq!(chip8.clock_hz(), u32::MAX);
+
+    assert_eq!(chip8.run_frame(), Ok(StepOutcome::Exited));
+    assert_eq!(chip8.run_frame(), Ok(StepOutcome::Exited));
+}
+
+#[test]
+fn timers_tick_at_60_hz_regardless_of_clock_speed() {
+    // V0 = 10, delay = V0, sound = V0, loop forever
+    let mut chip8 = machine(&[0x600A, 0xF015, 0xF018, 0x1206]);
+    chip8.set_instructions_per_frame(100);
+
+    run_frame(&mut chip8);
+    assert_eq!(chip8.register_delay, 9);
+    assert_eq!(chip8.register_sound, 9);
+
+    chip8.tick_timers(FRAME_DURATION / 2);
+    assert_eq!(chip8.register_delay, 9);
+    chip8.tick_timers(FRAME_DURATION * 7 / 2);
+    assert_eq!(chip8.register_delay, 5);
+    assert!(chip8.is_sound_playing());
+
+    chip8.tick_timers(Duration::from_secs(1));
+    assert_eq!(chip8.register_delay, 0);
+    assert!(!chip8.is_sound_playing());
+}
+
+#[test]
+fn logic_resets_vf_quirk() {
+    for (vf_reset, expected_vf) in [(true, 0), (false, 5)] {
+        // VF = 5, V1 |= V2
+        let mut chip8 = machine(&[0x6F05, 0x8121]);
+        chip8.set_quirks(Quirks {
+            vf_reset,
+            ..Quirks::MODERN
+        });
+        run(&mut chip8, 2);
+
+        assert_eq!(chip8.registers[0xF], expected_vf, "vf_reset: {vf_reset}");
+    }
+}
+
+#[test]
+fn jump_with_offset_quirk() {
+    for (jump_vx, expected_pc) in [(false, 0x312), (true, 0x314)] {
+        // V0 = 0x10, V3 = 0x12, jump to 0x302 + V0 (or + V3)
+        let mut chip8 = machine(&[0x6010, 0x6312, 0xB302]);
+        chip8.set_quirks(Quirks {
+            jump_vx,
+            ..Quirks::MODERN
+        });
+        run(&mut chip8, 3);
+
+        assert_eq!(chip8.pc, expected_pc, "jump_vx: {jump_vx}");
+    }
+}
+
+#[test]
+fn display_wait_quirk_ends_frame() {
+    for (display_wait, expected_v0) in [(true, 1), (false, 5)] {
+        // V0 += 1, draw, loop
+        let mut chip8 = machine(&[0x7001, 0xD001, 0x1200]);
+        chip8.set_quirks(Quirks {
+            display_wait,
+            ..Quirks::MODERN
+        });
+        chip8.set_instructions_per_frame(15);
+        run_frame(&mut chip8);
+
+        assert_eq!(
+            chip8.registers[0], expected_v0,
+            "display_wait: {display_wait}"
+        );
+    }
+}
+
+#[test]
+fn quirks_parse_from_their_names() {
+    for (name, quirks) in Quirks::PRESETS {
+        assert_eq!(name.parse(), Ok(quirks));
+    }
+    assert_eq!(
+        "octo".parse::<Quirks>(),
+        Err(ParseError::UnknownQuirks {
+            name: "octo".into()
+        })
+    );
+}
@@ -0,0 +1,471 @@
+//! Conformance of every instruction, executed one at a time on a fresh machine
+
+use super::*;
+
+/// `PC` advances to the following instruction
+const NEXT: u16 = OP_LENGTH;
+
+/// `PC` skips the following instruction
+const SKIP: u16 = OP_LENGTH * 2;
+
+/// Instruction, registers set before executing it, registers expected to change, and how far `PC`
+/// is expected to advance<br>
+/// Registers are given as `(register, value)`
+type RegisterCase = (u16, &'static [(usize, u8)], &'static [(usize, u8)], u16);
+
+/// Instructions that only involve registers, with the original interpreter's quirks
+#[rustfmt::skip]
+const REGISTER_CASES: &[RegisterCase] = &[
+    // 3XNN
+    (0x3312, &[(0x3, 0x12)], &[], SKIP),
+    (0x3313, &[(0x3, 0x12)], &[], NEXT),
+    // 4XNN
+    (0x4312, &[(0x3, 0x12)], &[], NEXT),
+    (0x4313, &[(0x3, 0x12)], &[], SKIP),
+    // 5XY0
+    (0x5120, &[(0x1, 0x07), (0x2, 0x07)], &[], SKIP),
+    (0x5120, &[(0x1, 0x07), (0x2, 0x08)], &[], NEXT),
+    // 6XNN
+    (0x6A42, &[], &[(0xA, 0x42)], NEXT),
+    // 7XNN does not touch VF
+    (0x7102, &[(0x1, 0xFF), (0xF, 0x55)], &[(0x1, 0x01)], NEXT),
+    // 8XY0
+    (0x8120, &[(0x2, 0x09)], &[(0x1, 0x09)], NEXT),
+    // 8XY1, 8XY2 and 8XY3 reset VF
+    (0x8121, &[(0x1, 0x0F), (0x2, 0xF0), (0xF, 0x55)], &[(0x1, 0xFF), (0xF, 0x00)], NEXT),
+    (0x8122, &[(0x1, 0x0F), (0x2, 0xFC), (0xF, 0x55)], &[(0x1, 0x0C), (0xF, 0x00)], NEXT),
+    (0x8123, &[(0x1, 0x0F), (0x2, 0xFF), (0xF, 0x55)], &[(0x1, 0xF0), (0xF, 0x00)], NEXT),
+    // 8XY4
+    (0x8124, &[(0x1, 0xF0), (0x2, 0x20)], &[(0x1, 0x10), (0xF, 0x01)], NEXT),
+    (0x8124, &[(0x1, 0x10), (0x2, 0x20), (0xF, 0x55)], &[(0x1, 0x30), (0xF, 0x00)], NEXT),
+    (0x8114, &[(0x1, 0x81)], &[(0x1, 0x02), (0xF, 0x01)], NEXT),
+    // 8XY5
+    (0x8125, &[(0x1, 0x30), (0x2, 0x10)], &[(0x1, 0x20), (0xF, 0x01)], NEXT),
+    (0x8125, &[(0x1, 0x10), (0x2, 0x30)], &[(0x1, 0xE0), (0xF, 0x00)], NEXT),
+    (0x8125, &[(0x1, 0x10), (0x2, 0x10)], &[(0x1, 0x00), (0xF, 0x01)], NEXT),
+    // 8XY6 shifts VY
+    (0x8126, &[(0x1, 0xFF), (0x2, 0x05)], &[(0x1, 0x02), (0xF, 0x01)], NEXT),
+    (0x8126, &[(0x2, 0x04), (0xF, 0x55)], &[(0x1, 0x02), (0xF, 0x00)], NEXT),
+    // 8XY7
+    (0x8127, &[(0x1, 0x10), (0x2, 0x30)], &[(0x1, 0x20), (0xF, 0x01)], NEXT),
+    (0x8127, &[(0x1, 0x30), (0x2, 0x10)], &[(0x1, 0xE0), (0xF, 0x00)], NEXT),
+    // 8XYE shifts VY
+    (0x812E, &[(0x2, 0x81)], &[(0x1, 0x02), (0xF, 0x01)], NEXT),
+    (0x812E, &[(0x2, 0x41), (0xF, 0x55)], &[(0x1, 0x82), (0xF, 0x00)], NEXT),
+    // When X = F, the flag overwrites the result
+    (0x8F14, &[(0xF, 0xFF), (0x1, 0x01)], &[(0xF, 0x01)], NEXT),
+    (0x8F15, &[(0xF, 0x10), (0x1, 0x20)], &[(0xF, 0x00)], NEXT),
+    (0x8F16, &[(0xF, 0x00), (0x1, 0x03)], &[(0xF, 0x01)], NEXT),
+    (0x8F17, &[(0xF, 0x10), (0x1, 0x20)], &[(0xF, 0x01)], NEXT),
+    (0x8F1E, &[(0xF, 0xFF), (0x1, 0x40)], &[(0xF, 0x00)], NEXT),
+    // When Y = F, the operand is read before the flag is written
+    (0x81F4, &[(0x1, 0xF0), (0xF, 0x20)], &[(0x1, 0x10), (0xF, 0x01)], NEXT),
+    (0x81F5, &[(0x1, 0x10), (0xF, 0x20)], &[(0x1, 0xF0), (0xF, 0x00)], NEXT),
+    // 9XY0
+    (0x9120, &[(0x1, 0x07), (0x2, 0x07)], &[], NEXT),
+    (0x9120, &[(0x1, 0x07), (0x2, 0x08)], &[], SKIP),
+    // CXNN with an empty mask
+    (0xC300, &[(0x3, 0x55)], &[(0x3, 0x00)], NEXT),
+];
+
+/// Instructions whose behaviour changes with the CHIP-48 quirks
+#[rustfmt::skip]
+const CHIP_48_REGISTER_CASES: &[RegisterCase] = &[
+    // 8XY1 leaves VF alone
+    (0x8121, &[(0x1, 0x0F), (0x2, 0xF0), (0xF, 0x55)], &[(0x1, 0xFF)], NEXT),
+    // 8XY6 and 8XYE shift VX in place
+    (0x8126, &[(0x1, 0x05), (0x2, 0xFF)], &[(0x1, 0x02), (0xF, 0x01)], NEXT),
+    (0x812E, &[(0x1, 0x81), (0x2, 0x00)], &[(0x1, 0x02), (0xF, 0x01)], NEXT),
+    (0x8F16, &[(0xF, 0x02)], &[(0xF, 0x00)], NEXT),
+];
+
+/// Execute each case on a fresh machine with `quirks`, and check every register and `PC`
+fn check_register_cases(quirks: Quirks, cases: &[RegisterCase]) {
+    for &(op, before, after, pc_offset) in cases {
+        let mut chip8 = machine(&[op]);
+        chip8.set_quirks(quirks);
+        for &(register, value) in before {
+            chip8.registers[register] = value;
+        }
+
+        let mut expected = chip8.registers;
+        for &(register, value) in after {
+            expected[register] = value;
+        }
+
+        assert_eq!(chip8.step(), Ok(StepOutcome::Executed), "{op:04X}");
+        assert_eq!(chip8.registers, expected, "{op:04X} with {before:02X?}");
+        assert_eq!(chip8.pc, CARTRIDGE_START_ADDR + pc_offset, "{op:04X}");
+        assert_eq!(chip8.register_i, 0, "{op:04X}");
+        assert_eq!(chip8.sp, 0, "{op:04X}");
+        assert!(chip8.screen().iter().all(|&px| px == 0), "{op:04X}");
+    }
+}
+
+/// Execute `op` on a fresh machine and check that it fails with `expected` without side effects
+fn check_error(op: u16, expected: Chip8Error) {
+    let mut chip8 = machine(&[op]);
+    let registers = chip8.registers;
+
+    assert_eq!(chip8.step(), Err(expected), "{op:04X}");
+    assert_eq!(chip8.pc, CARTRIDGE_START_ADDR, "{op:04X}");
+    assert_eq!(chip8.registers, registers, "{op:04X}");
+}
+
+/// Press `key` on the keypad
+fn press(chip8: &mut Chip8, key: usize) {
+    let mut keys: [Option<KeyState>; KEYS_COUNT] = Default::default();
+    keys[key] = Some(KeyState::Pressed);
+    chip8.set_keys(keys);
+}
+
+#[test]
+fn register_instructions() {
+    check_register_cases(Quirks::COSMAC_VIP, REGISTER_CASES);
+}
+
+#[test]
+fn register_instructions_with_chip_48_quirks() {
+    check_register_cases(Quirks::CHIP_48, CHIP_48_REGISTER_CASES);
+}
+
+#[test]
+fn invalid_instructions() {
+    let pc = CARTRIDGE_START_ADDR;
+    for op in [0x5121, 0x8128, 0x812F, 0x912F, 0xE1FF, 0xF1FF] {
+        check_error(op, Chip8Error::InvalidOpcode { pc, op });
+    }
+}
+
+#[test]
+fn machine_language_subroutine_is_unsupported() {
+    let pc = CARTRIDGE_START_ADDR;
+    check_error(0x0123, Chip8Error::UnsupportedOpcode { pc, op: 0x0123 });
+}
+
+#[test]
+fn clear_screen() {
+    let mut chip8 = machine(&[0x00E0]);
+    chip8.screen.pixels[0] = 1;
+    chip8.screen.pixels[SCREEN_BUF_SIZE - 1] = 1;
+    run(&mut chip8, 1);
+
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+    assert_eq!(chip8.pc, CARTRIDGE_START_ADDR + NEXT);
+}
+
+#[test]
+fn call_and_return() {
+    // 0x200: call 0x206, 0x202: V1 = 1, 0x204: loop, 0x206: return
+    let mut chip8 = machine(&[0x2206, 0x6101, 0x1204, 0x00EE]);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.pc, 0x206);
+    assert_eq!(chip8.sp, 1);
+    assert_eq!(chip8.stack[0], 0x200);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.pc, 0x202);
+    assert_eq!(chip8.sp, 0);
+
+    run(&mut chip8, 2);
+    assert_eq!(chip8.registers[1], 1);
+    assert_eq!(chip8.pc, 0x204);
+}
+
+#[test]
+fn stack_overflow() {
+    // Call itself forever
+    let mut chip8 = machine(&[0x2200]);
+    run(&mut chip8, STACK_SIZE);
+
+    assert_eq!(chip8.step(), Err(Chip8Error::StackOverflow { pc: 0x200 }));
+    assert_eq!(chip8.sp, STACK_SIZE);
+    assert_eq!(chip8.stack.len(), STACK_SIZE);
+}
+
+#[test]
+fn stack_underflow() {
+    check_error(0x00EE, Chip8Error::StackUnderflow { pc: 0x200 });
+}
+
+#[test]
+fn configurable_stack_size() {
+    // Call itself forever
+    let mut chip8 = machine(&[0x2200]);
+    chip8.set_stack_size(None);
+    run(&mut chip8, 100);
+    assert_eq!(chip8.sp, 100);
+
+    let mut chip8 = machine(&[0x2200]);
+    chip8.set_stack_size(Some(3));
+    chip8.reset();
+    chip8.load_cartridge(&[0x22, 0x00]).unwrap();
+    run(&mut chip8, 3);
+    assert_eq!(chip8.step(), Err(Chip8Error::StackOverflow { pc: 0x200 }));
+}
+
+#[test]
+fn stack_in_ram() {
+    // 0x200: call 0x206, 0x202: V1 = 1, 0x204: loop, 0x206: return
+    let mut chip8 = machine(&[0x2206, 0x6101, 0x1204, 0x00EE]);
+    chip8.set_stack_in_ram(true);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.ram[0xECE..0xED0], [0x02, 0x02]);
+    assert!(chip8.stack.is_empty());
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.pc, 0x202);
+    assert_eq!(chip8.sp, 0);
+}
+
+#[test]
+fn stack_in_ram_can_be_modified() {
+    // 0x200: call 0x206, 0x202: V1 = 1, 0x204: loop
+    // 0x206: I = 0xECE, V0 = 0x02, V1 = 0x04, store V0 and V1, return
+    let mut chip8 = machine(&[
+        0x2206, 0x6101, 0x1204, 0xAECE, 0x6002, 0x6104, 0xF155, 0x00EE,
+    ]);
+    chip8.set_stack_in_ram(true);
+    run(&mut chip8, 6);
+
+    assert_eq!(chip8.pc, 0x204);
+}
+
+#[test]
+fn stack_in_ram_overflow() {
+    // Call itself forever
+    let mut chip8 = machine(&[0x2200]);
+    chip8.set_stack_size(None);
+    chip8.set_stack_in_ram(true);
+    run(&mut chip8, 24);
+
+    assert_eq!(chip8.ram[VIP_STACK_ADDR as usize], 0x02);
+    assert_eq!(chip8.step(), Err(Chip8Error::StackOverflow { pc: 0x200 }));
+}
+
+#[test]
+fn jump() {
+    let mut chip8 = machine(&[0x1345]);
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.pc, 0x345);
+}
+
+#[test]
+fn jump_with_offset() {
+    // V0 = 0x10, V3 = 0x20, jump to 0x300 + V0
+    let mut chip8 = machine(&[0x6010, 0x6320, 0xB300]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.pc, 0x310);
+}
+
+#[test]
+fn jump_past_end_of_ram() {
+    let mut chip8 = machine(&[0x1FFF]);
+    run(&mut chip8, 1);
+
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0xFFF,
+            addr: 0x1000
+        })
+    );
+}
+
+#[test]
+fn set_i() {
+    let mut chip8 = machine(&[0xA345]);
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.register_i, 0x345);
+    assert_eq!(chip8.pc, CARTRIDGE_START_ADDR + NEXT);
+}
+
+#[test]
+fn random_is_masked() {
+    // V3 = random & 0x0F, forever
+    let mut chip8 = machine(&[0xC30F, 0x1200]);
+    for _ in 0..100 {
+        run(&mut chip8, 2);
+        assert_eq!(chip8.registers[3] & 0xF0, 0);
+    }
+}
+
+#[test]
+fn random_is_reproducible() {
+    // V3 = random, forever
+    let program = [0xC3FF, 0x1200];
+    let sequence = |seed| {
+        let mut chip8 = machine(&program);
+        chip8.set_random_seed(seed);
+        assert_eq!(chip8.random_seed(), Some(seed));
+        (0..16)
+            .map(|_| {
+                run(&mut chip8, 2);
+                chip8.registers[3]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(sequence(42), sequence(42));
+    assert_ne!(sequence(42), sequence(43));
+}
+
+#[test]
+fn random_resumes_mid_run() {
+    // V3 = random, forever
+    let mut chip8 = machine(&[0xC3FF, 0x1200]);
+    chip8.set_random_seed(7);
+    run(&mut chip8, 10);
+    assert_eq!(chip8.random_draws(), Some(5));
+
+    let mut resumed = SeededRandom::resume(7, 5);
+    for _ in 0..8 {
+        run(&mut chip8, 2);
+        assert_eq!(chip8.registers[3], resumed.next_byte());
+    }
+    assert_eq!(resumed.draws(), Some(13));
+}
+
+#[test]
+fn random_source() {
+    /// Counts from 0
+    #[derive(Debug)]
+    struct Counter(u8);
+
+    impl RandomSource for Counter {
+        fn next_byte(&mut self) -> u8 {
+            self.0 += 1;
+            self.0 - 1
+        }
+    }
+
+    // V3 = random & 0xFE, V4 = random & 0xFE
+    let mut chip8 = machine(&[0xC3FE, 0xC4FE]);
+    chip8.set_random_source(Counter(3));
+    run(&mut chip8, 2);
+
+    assert_eq!(chip8.registers[3..5], [2, 4]);
+    assert_eq!(chip8.random_seed(), None);
+    assert_eq!(chip8.random_draws(), None);
+}
+
+#[test]
+fn vip_random() {
+    let mut page = [0; 256];
+    page[..4].copy_from_slice(&[0x10, 0x20, 0xF0, 0x30]);
+    let mut random = VipRandom::new(page, 0);
+
+    let bytes: Vec<u8> = (0..3).map(|_| random.next_byte()).collect();
+    assert_eq!(bytes, [0x20, 0x10, 0x40]);
+    assert_eq!(random.seed(), Some(0));
+    assert_eq!(random.draws(), Some(3));
+}
+
+#[test]
+fn draw_past_end_of_ram() {
+    // I = 0xFFE, draw 3 rows
+    let mut chip8 = machine(&[0xAFFE, 0xD013]);
+    run(&mut chip8, 1);
+
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0x202,
+            addr: 0x1000
+        })
+    );
+    assert_eq!(chip8.pc, 0x202);
+}
+
+#[test]
+fn skip_if_key() {
+    for (op, pressed, pc_offset) in [
+        (0xE59E, true, SKIP),
+        (0xE59E, false, NEXT),
+        (0xE5A1, true, NEXT),
+        (0xE5A1, false, SKIP),
+    ] {
+        let mut chip8 = machine(&[op]);
+        chip8.registers[5] = 0xB;
+        if pressed {
+            press(&mut chip8, 0xB);
+        }
+        run(&mut chip8, 1);
+
+        assert_eq!(chip8.pc, CARTRIDGE_START_ADDR + pc_offset, "{op:04X}");
+    }
+}
+
+#[test]
+fn skip_if_invalid_key() {
+    for op in [0xE59E, 0xE5A1] {
+        let mut chip8 = machine(&[op]);
+        chip8.registers[5] = 0x10;
+
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::InvalidKey {
+                pc: 0x200,
+                key: 0x10
+            })
+        );
+    }
+}
+
+#[test]
+fn wait_for_key() {
+    // V3 = key, V4 = 1
+    let mut chip8 = machine(&[0xF30A, 0x6401]);
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.step(), Ok(StepOutcome::WaitingForKey));
+    assert_eq!(chip8.step(), Ok(StepOutcome::WaitingForKey));
+    assert_eq!(chip8.registers[4], 0);
+
+    press(&mut chip8, 7);
+    run(&mut chip8, 1);
+    assert_eq!(chip8.registers[3], 7);
+    assert_eq!(chip8.registers[4], 1);
+}
+
+#[test]
+fn timers() {
+    // V1 = 0x20, delay = V1, sound = V1, V2 = delay
+    let mut chip8 = machine(&[0x6120, 0xF115, 0xF118, 0xF207]);
+    run(&mut chip8, 3);
+    assert_eq!(chip8.register_delay, 0x20);
+    assert_eq!(chip8.register_sound, 0x20);
+
+    chip8.tick_timers(FRAME_DURATION * 2);
+    run(&mut chip8, 1);
+    assert_eq!(chip8.registers[2], 0x1E);
+}
+
+#[test]
+fn add_vx_to_i() {
+    // I = 0x300, V2 = 0x10, I += V2
+    let mut chip8 = machine(&[0xA300, 0x6210, 0xF21E]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.register_i, 0x310);
+    assert_eq!(chip8.registers[0xF], 0);
+}
+
+#[test]
+fn set_i_to_font() {
+    // V1 = 0xA, I = font sprite of V1
+    let mut chip8 = machine(&[0x610A, 0xF129]);
+    run(&mut chip8, 2);
+
+    let glyph = FONT_SPRITES_ADDR + 0xA * FONT_SPRITE_SIZE;
+    assert_eq!(chip8.register_i, glyph as u32);
+
+    let start = glyph as usize;
+    let end = start + FONT_SPRITE_SIZE as usize;
+    assert_eq!(chip8.ram[start..end], FontSet::default().glyphs()[50..55]);
+}
@@ -0,0 +1,130 @@
+//! Machines built from a platform description
+
+use super::*;
+
+#[test]
+fn machine_matches_platform() {
+    for platform in Platform::ALL {
+        let spec = platform.spec();
+        let chip8 = Chip8::with_platform(platform);
+
+        assert_eq!(chip8.platform(), platform);
+        assert_eq!(chip8.ram.len(), spec.ram_size, "{}", spec.name);
+        assert_eq!(chip8.stack_size(), Some(spec.stack_size), "{}", spec.name);
+        assert_eq!(chip8.screen_width(), spec.screen_width, "{}", spec.name);
+        assert_eq!(chip8.screen_height(), spec.screen_height, "{}", spec.name);
+        assert_eq!(chip8.pc, spec.start_addr, "{}", spec.name);
+        assert_eq!(
+            chip8.instruction_set(),
+            spec.instruction_set,
+            "{}",
+            spec.name
+        );
+        assert_eq!(chip8.quirks(), spec.quirks, "{}", spec.name);
+    }
+}
+
+#[test]
+fn load_at_platform_start_address() {
+    let mut chip8 = platform_machine(Platform::Eti660, &[0x6042]);
+    assert_eq!(chip8.ram[0x600..0x602], [0x60, 0x42]);
+    assert_eq!(chip8.ram[0x200..0x202], [0, 0]);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.registers[0], 0x42);
+    assert_eq!(chip8.pc, 0x602);
+}
+
+#[test]
+fn cartridge_fits_after_platform_start_address() {
+    let mut chip8 = Chip8::with_platform(Platform::Eti660);
+    let rom = vec![0; RAM_SIZE - 0x600 + 1];
+
+    assert_eq!(
+        chip8.load_cartridge(&rom),
+        Err(CartridgeError::TooLarge {
+            size: rom.len(),
+            max: RAM_SIZE - 0x600
+        })
+    );
+}
+
+#[test]
+fn stack_depth_depends_on_platform() {
+    for (platform, depth) in [(Platform::Chip8, 12), (Platform::Chip48, 16)] {
+        // Call 0x200, forever
+        let mut chip8 = platform_machine(platform, &[0x2200]);
+        run(&mut chip8, depth);
+
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::StackOverflow { pc: 0x200 }),
+            "{platform:?}"
+        );
+    }
+}
+
+#[test]
+fn reset_keeps_platform() {
+    let mut chip8 = platform_machine(Platform::XoChip, &[0x00E0]);
+    chip8.reset();
+
+    assert_eq!(chip8.platform(), Platform::XoChip);
+    assert_eq!(chip8.ram.len(), XO_CHIP_RAM_SIZE);
+}
+
+#[test]
+fn platforms_parse_from_their_names() {
+    for platform in Platform::ALL {
+        assert_eq!(platform.name().parse(), Ok(platform));
+    }
+    assert_eq!(
+        "chip-8".parse::<Platform>(),
+        Err(ParseError::UnknownPlatform {
+            name: "chip-8".into()
+        })
+    );
+}
+
+#[test]
+fn hires_chip8_starts_at_0x2c0() {
+    let mut chip8 = platform_machine(Platform::Chip8HiRes, &[0x6042]);
+    assert_eq!(chip8.ram[0x2C0..0x2C2], [0x60, 0x42]);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.registers[0], 0x42);
+}
+
+#[test]
+fn hires_chip8_clear_screen() {
+    // I = font, V0 = 60, draw at (0, 60), clear
+    let mut chip8 = platform_machine(Platform::Chip8HiRes, &[0xA050, 0x613C, 0xD015, 0x0230]);
+    run(&mut chip8, 3);
+    assert_eq!(screen_rect(&chip8, 0, 60, 4, 1), ["####"]);
+
+    run(&mut chip8, 1);
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+    assert_eq!(chip8.pc, 0x2C0 + 4 * OP_LENGTH);
+}
+
+#[test]
+fn hires_clear_screen_needs_hires_interpreter() {
+    let mut chip8 = machine(&[0x0230]);
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::UnsupportedOpcode {
+            pc: 0x200,
+            op: 0x0230
+        })
+    );
+}
+
+#[test]
+fn eti_660_display_is_48_pixels_high() {
+    // I = font, V1 = 46, draw at (0, 46)
+    let mut chip8 = platform_machine(Platform::Eti660, &[0xA050, 0x612E, 0xD012]);
+    run(&mut chip8, 3);
+
+    assert_eq!(chip8.screen().len(), 64 * 48);
+    assert_eq!(screen_rect(&chip8, 0, 46, 4, 2), ["####", "#..#"]);
+}
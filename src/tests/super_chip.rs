@@ -0,0 +1,182 @@
+//! SUPER-CHIP 1.1 instructions
+
+use super::*;
+
+/// Create a SUPER-CHIP machine with `program` loaded at the start address
+fn super_chip_machine(program: &[u16]) -> Chip8 {
+    platform_machine(Platform::SuperChip, program)
+}
+
+#[test]
+fn super_chip_instructions_need_super_chip() {
+    for op in [0x00C1, 0x00FB, 0x00FC, 0x00FD, 0x00FE, 0x00FF] {
+        let mut chip8 = machine(&[op]);
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::UnsupportedOpcode { pc: 0x200, op }),
+            "{op:04X}"
+        );
+    }
+    for op in [0xF030, 0xF075, 0xF085] {
+        let mut chip8 = machine(&[op]);
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::InvalidOpcode { pc: 0x200, op }),
+            "{op:04X}"
+        );
+    }
+}
+
+#[test]
+fn switch_resolution() {
+    // High resolution, I = font, draw, low resolution
+    let mut chip8 = super_chip_machine(&[0x00FF, 0xA050, 0xD001, 0x00FE]);
+    assert_eq!((chip8.screen_width(), chip8.screen_height()), (64, 32));
+
+    run(&mut chip8, 3);
+    assert_eq!((chip8.screen_width(), chip8.screen_height()), (128, 64));
+    assert_eq!(
+        chip8.screen().len(),
+        HIRES_SCREEN_WIDTH * HIRES_SCREEN_HEIGHT
+    );
+    assert!(chip8.screen().contains(&1));
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.screen().len(), SCREEN_BUF_SIZE);
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+}
+
+#[test]
+fn draw_in_high_resolution_wraps_at_128_pixels() {
+    // High resolution, V0 = 130, V1 = 70, I = sprite, draw
+    let mut chip8 = super_chip_machine(&[0x00FF, 0x6082, 0x6146, 0xA000 | SPRITE_ADDR, 0xD011]);
+    chip8.ram[SPRITE_ADDR as usize] = 0xC0;
+    run(&mut chip8, 5);
+
+    assert_eq!(screen_rect(&chip8, 0, 6, 5, 1), ["..##."]);
+}
+
+#[test]
+fn draw_large_sprite() {
+    // I = sprite, draw 16x16 at (0, 0)
+    let mut chip8 = super_chip_machine(&[0xA000 | SPRITE_ADDR, 0xD000]);
+    let start = SPRITE_ADDR as usize;
+    chip8.ram[start..start + 32].fill(0x00);
+    chip8.ram[start..start + 4].copy_from_slice(&[0x80, 0x01, 0xFF, 0xFF]);
+    chip8.ram[start + 30..start + 32].copy_from_slice(&[0x00, 0x01]);
+    run(&mut chip8, 2);
+
+    assert_eq!(
+        screen_rect(&chip8, 0, 0, 17, 2),
+        ["#..............#.", "################."]
+    );
+    assert_eq!(screen_rect(&chip8, 0, 15, 17, 1), ["...............#."]);
+    assert_eq!(chip8.screen().iter().filter(|&&px| px == 1).count(), 19);
+}
+
+#[test]
+fn draw_large_sprite_past_end_of_ram() {
+    // I = 0xFF0, draw 16x16
+    let mut chip8 = super_chip_machine(&[0xAFF0, 0xD000]);
+    run(&mut chip8, 1);
+
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::MemoryOutOfBounds {
+            pc: 0x202,
+            addr: 0x1000
+        })
+    );
+}
+
+#[test]
+fn scroll() {
+    // I = sprite, draw at (4, 0), scroll down 2, scroll right, scroll left twice
+    let mut chip8 = super_chip_machine(&[
+        0xA000 | SPRITE_ADDR,
+        0x6004,
+        0xD011,
+        0x00C2,
+        0x00FB,
+        0x00FC,
+        0x00FC,
+    ]);
+    chip8.ram[SPRITE_ADDR as usize] = 0xF0;
+
+    run(&mut chip8, 4);
+    assert_eq!(
+        screen_rect(&chip8, 0, 0, 12, 3),
+        ["............", "............", "....####...."]
+    );
+    run(&mut chip8, 1);
+    assert_eq!(screen_rect(&chip8, 0, 2, 12, 1), ["........####"]);
+    run(&mut chip8, 2);
+    assert_eq!(screen_rect(&chip8, 0, 2, 12, 1), ["####........"]);
+}
+
+#[test]
+fn scroll_past_the_edge_clears_the_screen() {
+    // I = sprite, draw at (0, 0), scroll left
+    let mut chip8 = super_chip_machine(&[0xA000 | SPRITE_ADDR, 0xD011, 0x00FC]);
+    chip8.ram[SPRITE_ADDR as usize] = 0xF0;
+    run(&mut chip8, 3);
+
+    assert!(chip8.screen().iter().all(|&px| px == 0));
+}
+
+#[test]
+fn set_i_to_large_font() {
+    // V0 = 0x1A, FX30
+    let mut chip8 = super_chip_machine(&[0x601A, 0xF030]);
+    run(&mut chip8, 2);
+
+    let addr = FONT_SPRITES_ADDR as usize + FONT_SIZE + 0xA * LARGE_FONT_SPRITE_SIZE as usize;
+    assert_eq!(chip8.register_i as usize, addr);
+    assert_eq!(chip8.ram[addr], 0x7E);
+}
+
+#[test]
+fn store_and_load_rpl_flags() {
+    // V0 = 1, V1 = 2, F175, V0 = 0, V1 = 0, F085
+    let mut chip8 = super_chip_machine(&[0x6001, 0x6102, 0xF175, 0x6000, 0x6100, 0xF085]);
+    run(&mut chip8, 6);
+
+    assert_eq!(chip8.rpl_flags()[..3], [1, 2, 0]);
+    assert_eq!(chip8.registers[..2], [1, 0]);
+}
+
+#[test]
+fn rpl_flags_survive_reset() {
+    let mut chip8 = super_chip_machine(&[0xF085]);
+    chip8.set_rpl_flags([7; RPL_FLAGS_COUNT]);
+    chip8.reset();
+    chip8.load_cartridge(&[0xF0, 0x85]).unwrap();
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.instruction_set(), InstructionSet::SuperChip);
+    assert_eq!(chip8.registers[0], 7);
+}
+
+#[test]
+fn only_eight_rpl_flags() {
+    let mut chip8 = super_chip_machine(&[0xF875]);
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::InvalidOpcode {
+            pc: 0x200,
+            op: 0xF875
+        })
+    );
+}
+
+#[test]
+fn exit() {
+    // V0 += 1, exit, V0 += 1
+    let mut chip8 = super_chip_machine(&[0x7001, 0x00FD, 0x7001]);
+    chip8.set_instructions_per_frame(10);
+
+    assert_eq!(chip8.run_frame(), Ok(StepOutcome::Exited));
+    assert_eq!(chip8.step(), Ok(StepOutcome::Exited));
+    assert_eq!(chip8.registers[0], 1);
+    assert_eq!(chip8.pc, 0x202);
+}
@@ -0,0 +1,205 @@
+//! XO-CHIP instructions
+
+use super::*;
+
+/// Create an XO-CHIP machine with `program` loaded at the start address
+fn xo_chip_machine(program: &[u16]) -> Chip8 {
+    platform_machine(Platform::XoChip, program)
+}
+
+#[test]
+fn xo_chip_instructions_need_xo_chip() {
+    let mut chip8 = Chip8::with_platform(Platform::SuperChip);
+    for op in [0x5122, 0x5123, 0xF000, 0xF101, 0xF002, 0xF03A] {
+        let rom = u16::to_be_bytes(op);
+        chip8.load_cartridge(&rom).unwrap();
+        assert_eq!(
+            chip8.step(),
+            Err(Chip8Error::InvalidOpcode { pc: 0x200, op }),
+            "{op:04X}"
+        );
+    }
+}
+
+#[test]
+fn ram_is_64_kib() {
+    let mut chip8 = xo_chip_machine(&[0x00E0]);
+    let rom = vec![0xAA; XO_CHIP_RAM_SIZE - CARTRIDGE_START_ADDR as usize];
+    assert_eq!(chip8.load_cartridge(&rom), Ok(()));
+
+    let mut chip8 = Chip8::default();
+    assert_eq!(
+        chip8.load_cartridge(&rom),
+        Err(CartridgeError::TooLarge {
+            size: rom.len(),
+            max: RAM_SIZE - CARTRIDGE_START_ADDR as usize
+        })
+    );
+}
+
+#[test]
+fn set_i_long() {
+    // I = 0xBEEF, V0 = 1
+    let mut chip8 = xo_chip_machine(&[0xF000, 0xBEEF, 0x6001]);
+    run(&mut chip8, 2);
+
+    assert_eq!(chip8.register_i, 0xBEEF);
+    assert_eq!(chip8.registers[0], 1);
+}
+
+#[test]
+fn pc_wraps_at_end_of_ram() {
+    // V0 = 0x42 in the last two bytes of RAM
+    let mut chip8 = xo_chip_machine(&[0x00E0]);
+    chip8.ram[0xFFFE..].copy_from_slice(&[0x60, 0x42]);
+    chip8.pc = 0xFFFE;
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.registers[0], 0x42);
+    assert_eq!(chip8.pc, 0x0000);
+}
+
+#[test]
+fn set_i_long_wraps_at_end_of_ram() {
+    // I = 0xBEEF in the last four bytes of RAM
+    let mut chip8 = xo_chip_machine(&[0x00E0]);
+    chip8.ram[0xFFFC..].copy_from_slice(&[0xF0, 0x00, 0xBE, 0xEF]);
+    chip8.pc = 0xFFFC;
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.register_i, 0xBEEF);
+    assert_eq!(chip8.pc, 0x0000);
+
+    // F000 in the last two bytes of RAM, its address in the first two
+    chip8.ram[0xFFFE..].copy_from_slice(&[0xF0, 0x00]);
+    chip8.ram[..2].copy_from_slice(&[0x12, 0x34]);
+    chip8.pc = 0xFFFE;
+    run(&mut chip8, 1);
+
+    assert_eq!(chip8.register_i, 0x1234);
+    assert_eq!(chip8.pc, 0x0002);
+}
+
+#[test]
+fn skip_over_set_i_long() {
+    // Skip if V0 == 0, I = 0x1234, V1 = 1
+    let mut chip8 = xo_chip_machine(&[0x3000, 0xF000, 0x1234, 0x6101]);
+    run(&mut chip8, 2);
+
+    assert_eq!(chip8.register_i, 0);
+    assert_eq!(chip8.registers[1], 1);
+}
+
+#[test]
+fn store_and_load_register_ranges() {
+    // V1 = 1, V2 = 2, V3 = 3, I = 0x300, store V1..V3, I = 0x310, store V3..V1
+    let mut chip8 = xo_chip_machine(&[
+        0x6101, 0x6202, 0x6303, 0xA300, 0x5132, 0xA310, 0x5312, 0xA300, 0x5373,
+    ]);
+    run(&mut chip8, 7);
+    assert_eq!(chip8.ram[0x300..0x304], [1, 2, 3, 0]);
+    assert_eq!(chip8.ram[0x310..0x314], [3, 2, 1, 0]);
+    assert_eq!(chip8.register_i, 0x310);
+
+    // I = 0x300, load V3..V7
+    run(&mut chip8, 2);
+    assert_eq!(chip8.registers[3..8], [1, 2, 3, 0, 0]);
+    assert_eq!(chip8.register_i, 0x300);
+}
+
+#[test]
+fn draw_on_both_planes() {
+    // Select both planes, I = sprite, draw, select plane 2, clear
+    let mut chip8 = xo_chip_machine(&[0xF301, 0xA000 | SPRITE_ADDR, 0xD001, 0xF201, 0x00E0]);
+    let start = SPRITE_ADDR as usize;
+    chip8.ram[start..start + 2].copy_from_slice(&[0xC0, 0x60]);
+
+    run(&mut chip8, 3);
+    assert_eq!(chip8.screen()[..4], [1, 3, 2, 0]);
+    assert_eq!(chip8.registers[0xF], 0);
+
+    run(&mut chip8, 2);
+    assert_eq!(chip8.screen()[..4], [1, 1, 0, 0]);
+}
+
+#[test]
+fn draw_collides_on_any_plane() {
+    // Select plane 2, I = sprite, draw, select both planes, I = sprite - 1, draw
+    let mut chip8 = xo_chip_machine(&[
+        0xF201,
+        0xA000 | SPRITE_ADDR,
+        0xD001,
+        0xF301,
+        0xA000 | (SPRITE_ADDR - 1),
+        0xD001,
+    ]);
+    let start = SPRITE_ADDR as usize;
+    chip8.ram[start - 1..start + 1].copy_from_slice(&[0x00, 0x80]);
+    run(&mut chip8, 3);
+    assert_eq!(chip8.screen()[0], 2);
+
+    run(&mut chip8, 3);
+    assert_eq!(chip8.screen()[0], 0);
+    assert_eq!(chip8.registers[0xF], 1);
+}
+
+#[test]
+fn invalid_plane_selection() {
+    let mut chip8 = xo_chip_machine(&[0xF401]);
+    assert_eq!(
+        chip8.step(),
+        Err(Chip8Error::InvalidOpcode {
+            pc: 0x200,
+            op: 0xF401
+        })
+    );
+}
+
+#[test]
+fn scroll_selected_planes_only() {
+    // Select both planes, I = sprite, draw, select plane 1, scroll down 1, scroll up 1, scroll up 1
+    let mut chip8 = xo_chip_machine(&[
+        0xF301,
+        0xA000 | SPRITE_ADDR,
+        0xD001,
+        0xF101,
+        0x00C1,
+        0x00D1,
+        0x00D1,
+    ]);
+    let start = SPRITE_ADDR as usize;
+    chip8.ram[start..start + 2].copy_from_slice(&[0x80, 0x80]);
+
+    run(&mut chip8, 5);
+    assert_eq!(chip8.screen()[0], 2);
+    assert_eq!(chip8.screen()[SCREEN_WIDTH], 1);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.screen()[0], 3);
+
+    run(&mut chip8, 1);
+    assert_eq!(chip8.screen()[0], 2);
+}
+
+#[test]
+fn audio_pattern_and_pitch() {
+    // I = 0x300, load audio pattern, V0 = 112, pitch = V0
+    let mut chip8 = xo_chip_machine(&[0xA300, 0xF002, 0x6070, 0xF03A]);
+    chip8.ram[0x300..0x310].copy_from_slice(&[0xF0; AUDIO_PATTERN_SIZE]);
+    assert_eq!(chip8.pitch(), DEFAULT_PITCH);
+    assert_eq!(chip8.audio_playback_rate(), 4000.0);
+
+    run(&mut chip8, 4);
+    assert_eq!(chip8.audio_pattern(), &[0xF0; AUDIO_PATTERN_SIZE]);
+    assert_eq!(chip8.pitch(), 112);
+    assert_eq!(chip8.audio_playback_rate(), 8000.0);
+}
+
+#[test]
+fn sixteen_rpl_flags() {
+    // VF = 9, FF75
+    let mut chip8 = xo_chip_machine(&[0x6F09, 0xFF75]);
+    run(&mut chip8, 2);
+
+    assert_eq!(chip8.rpl_flags()[15], 9);
+}
@@ -0,0 +1,98 @@
+//! Instructions added by XO-CHIP
+
+use crate::screen::PLANES_COUNT;
+use crate::{Chip8, Chip8Error, OP_LENGTH};
+
+/// Size of the RAM of XO-CHIP machines, in bytes
+pub const XO_CHIP_RAM_SIZE: usize = 0x10000;
+
+/// Size of the audio pattern buffer, in bytes (128 one-bit samples)
+pub const AUDIO_PATTERN_SIZE: usize = 16;
+
+/// Value of the pitch register at power on, for a playback rate of 4000 samples per second
+pub const DEFAULT_PITCH: u8 = 64;
+
+impl Chip8 {
+    /// op: `00DN`
+    /// Scroll the selected planes up by `N` pixels
+    pub(crate) fn scroll_up(&mut self, op: u16) {
+        let n = (op & 0x000F) as usize;
+        self.screen.scroll_up(n);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+
+    /// op: `5XY2`
+    /// Store the values of registers `VX` to `VY` inclusive in memory starting at address `I`<br>
+    /// The registers are stored in reverse order if `X` is greater than `Y`, and `I` is left unchanged
+    pub(crate) fn store_register_range(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let registers = self.register_range(op);
+        self.check_ram_range(self.register_i, registers.len())?;
+        for (offset, vi) in registers.enumerate() {
+            self.ram[self.register_i as usize + offset] = self.registers[vi];
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `5XY3`
+    /// Fill registers `VX` to `VY` inclusive with the values stored in memory starting at address `I`<br>
+    /// The registers are filled in reverse order if `X` is greater than `Y`, and `I` is left unchanged
+    pub(crate) fn load_register_range(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let registers = self.register_range(op);
+        self.check_ram_range(self.register_i, registers.len())?;
+        for (offset, vi) in registers.enumerate() {
+            self.registers[vi] = self.ram[self.register_i as usize + offset];
+        }
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// Registers from `X` to `Y` inclusive of a `5XY2` or `5XY3` instruction, in transfer order
+    fn register_range(&self, op: u16) -> impl ExactSizeIterator<Item = usize> {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        let vy = ((op & 0x00F0) >> 4) as usize;
+        let count = vx.abs_diff(vy) + 1;
+        (0..count).map(move |i| if vx <= vy { vx + i } else { vx - i })
+    }
+
+    /// op: `F000 NNNN`
+    /// Store the 16-bit address `NNNN`, held by the following two bytes, in register `I`
+    pub(crate) fn set_i_long(&mut self) -> Result<(), Chip8Error> {
+        let hi = self.read_ram(self.pc.wrapping_add(OP_LENGTH) as u32)?;
+        let lo = self.read_ram(self.pc.wrapping_add(OP_LENGTH + 1) as u32)?;
+        self.register_i = u16::from_be_bytes([hi, lo]) as u32;
+        self.pc = self.pc.wrapping_add(OP_LENGTH * 2);
+        Ok(())
+    }
+
+    /// op: `FN01`
+    /// Select the bitplanes affected by drawing, clearing and scrolling, `N` being a mask of planes
+    pub(crate) fn select_planes(&mut self, op: u16) -> Result<(), Chip8Error> {
+        let planes = ((op & 0x0F00) >> 8) as u8;
+        if planes >> PLANES_COUNT != 0 {
+            return Err(Chip8Error::InvalidOpcode { pc: self.pc, op });
+        }
+        self.screen.planes = planes;
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `F002`
+    /// Fill the audio pattern buffer with the 16 bytes stored in memory starting at address `I`
+    pub(crate) fn load_audio_pattern(&mut self) -> Result<(), Chip8Error> {
+        self.check_ram_range(self.register_i, AUDIO_PATTERN_SIZE)?;
+        let start = self.register_i as usize;
+        self.audio_pattern
+            .copy_from_slice(&self.ram[start..start + AUDIO_PATTERN_SIZE]);
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+        Ok(())
+    }
+
+    /// op: `FX3A`
+    /// Set the pitch register to the value of register `VX`
+    pub(crate) fn set_pitch(&mut self, op: u16) {
+        let vx = ((op & 0x0F00) >> 8) as usize;
+        self.pitch = self.registers[vx];
+        self.pc = self.pc.wrapping_add(OP_LENGTH);
+    }
+}
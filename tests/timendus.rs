@@ -0,0 +1,57 @@
+//! Run the bundled Timendus test ROMs headlessly and compare the display against golden snapshots
+//!
+//! Set `UPDATE_GOLDEN=1` to write the current display to the snapshots instead of comparing.
+
+use chip8::headless::{compare_snapshots, run_frames, snapshot};
+use chip8::{Chip8, Quirks};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Number of frames each ROM runs for before the display is compared
+const FRAMES: u32 = 120;
+
+/// Run `rom` from the `files` directory and compare its display with `tests/golden/<rom>.txt`
+fn check_rom(rom: &str, quirks: Quirks) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let rom_path = root.join("files").join(rom);
+    let golden_path = root.join("tests/golden").join(rom).with_extension("txt");
+
+    let mut chip8 = Chip8::new(quirks);
+    chip8.load_cartridge(&fs::read(&rom_path).unwrap()).unwrap();
+    if let Err(e) = run_frames(&mut chip8, FRAMES) {
+        panic!("{rom}: {e}");
+    }
+    let actual = snapshot(&chip8);
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden_path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&golden_path)
+        .unwrap_or_else(|e| panic!("{}: {e}", golden_path.display()));
+    if let Err(diff) = compare_snapshots(&expected, &actual) {
+        panic!("{rom}: display differs from the golden snapshot\n{diff}");
+    }
+}
+
+#[test]
+fn chip8_logo() {
+    check_rom("timendus_v4.1_1-chip8-logo.ch8", Quirks::COSMAC_VIP);
+}
+
+#[test]
+fn ibm_logo() {
+    check_rom("timendus_v4.1_2-ibm-logo.ch8", Quirks::COSMAC_VIP);
+}
+
+#[test]
+fn corax_plus() {
+    check_rom("timendus_v4.1_3-corax+.ch8", Quirks::COSMAC_VIP);
+}
+
+#[test]
+fn timendus_v1() {
+    check_rom("timendus_v1.ch8", Quirks::COSMAC_VIP);
+}